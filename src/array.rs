@@ -1,16 +1,27 @@
-use std::{collections::HashMap, ops::Range};
+use std::{borrow::Cow, cmp::Ordering, collections::HashMap, ops::Range, sync::Arc};
 
-use futures::future::try_join_all;
+use futures::{stream, Stream, StreamExt, TryStreamExt};
+use itertools::Itertools;
+use ndarray::{ArrayViewMutD, Slice};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::{
-    chunk::{decode_chunk, encode_chunk},
+    chunk::{decode_chunk, decode_chunk_into, encode_chunk, CodecExecution},
     codec_registry::CodecRegistry,
+    copy::{copy_prefix, dst_has_size, CopyOptions, CopyReport},
+    data_type_registry::DataTypeRegistry,
+    element::Element,
     error::CharizarrError,
-    index::BasicIndexIterator,
+    index::{
+        BasicIndexIterator, ChunkProjection, CoordinateIndexIterator, OrthogonalIndexIterator,
+        Projection,
+    },
     metadata::{DataType, Extension, NodeType, ZarrFormat},
-    store::{ListableStore, ReadableStore, WriteableStore},
+    rechunk::{block_shape, RechunkOptions},
+    selection::{BasicSelection, CoordinateSelection, OrthogonalSelection},
+    store::{erase_node, ListableStore, ReadableStore, WriteableStore},
+    typed_array::TypedArray,
     zarray::ZArray,
 };
 
@@ -22,7 +33,6 @@ pub struct ArrayMetadata {
     pub data_type: DataType,
     pub chunk_grid: Extension,
     pub chunk_key_encoding: Extension,
-    // TODO: Actual fill values
     pub fill_value: Value,
     pub codecs: Vec<Extension>,
     pub attributes: Option<HashMap<String, Value>>,
@@ -30,12 +40,28 @@ pub struct ArrayMetadata {
     pub dimension_names: Option<Vec<String>>,
 }
 
+/// The default number of chunks read or written at the same time
+pub const DEFAULT_CONCURRENCY: usize = 32;
+
+/// The order chunks are yielded by `Array::iter_chunks`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChunkOrder {
+    /// In row major order of the chunk grid
+    #[default]
+    Ordered,
+    /// As soon as each chunk has been read
+    Unordered,
+}
+
 pub struct Array<'a, T>
 where
     T: ReadableStore + ListableStore + WriteableStore,
 {
     store: &'a T,
-    codec_registry: CodecRegistry,
+    codec_registry: Arc<CodecRegistry>,
+    data_type_registry: Arc<DataTypeRegistry>,
+    concurrency: usize,
+    codec_execution: CodecExecution,
     pub metadata: ArrayMetadata,
     pub path: String,
 }
@@ -61,7 +87,10 @@ where
 
         Ok(Self {
             store,
-            codec_registry,
+            codec_registry: Arc::new(codec_registry),
+            data_type_registry: Arc::new(DataTypeRegistry::default()),
+            concurrency: DEFAULT_CONCURRENCY,
+            codec_execution: CodecExecution::default(),
             metadata: meta,
             path,
         })
@@ -117,12 +146,42 @@ where
 
         Ok(Self {
             store,
-            codec_registry,
+            codec_registry: Arc::new(codec_registry),
+            data_type_registry: Arc::new(DataTypeRegistry::default()),
+            concurrency: DEFAULT_CONCURRENCY,
+            codec_execution: CodecExecution::default(),
             metadata,
             path,
         })
     }
 
+    /// Limit the number of chunks that are read, written or erased at the
+    /// same time. The limit is at least one.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// The maximum number of chunks that are read, written or erased at the
+    /// same time
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+
+    /// Choose where chunks are encoded and decoded, by default on the tokio
+    /// blocking thread pool
+    pub fn with_codec_execution(mut self, codec_execution: CodecExecution) -> Self {
+        self.codec_execution = codec_execution;
+        self
+    }
+
+    /// Use the given registry to resolve extension data types that are not
+    /// built into the crate
+    pub fn with_data_type_registry(mut self, data_type_registry: DataTypeRegistry) -> Self {
+        self.data_type_registry = Arc::new(data_type_registry);
+        self
+    }
+
     pub fn chunk_key_separator(&self) -> &str {
         self.metadata.chunk_key_encoding.configuration["separator"]
             .as_str()
@@ -154,15 +213,98 @@ where
     /// and the codecs provided to the array's registry
     pub async fn get_chunk(&self, id: &[usize]) -> Result<ZArray, CharizarrError> {
         let bytes = self.get_raw_chunk(id).await?;
-        let data_type = self.dtype();
-        let chunk = decode_chunk(
-            &self.codec_registry,
-            &self.metadata.codecs,
-            data_type,
-            bytes,
-        )?
-        .reshape(&self.chunk_shape());
-        Ok(chunk)
+        self.decode_raw_chunk(id, bytes).await
+    }
+
+    /// Decode the raw bytes of a chunk into a chunk of the chunk shape
+    async fn decode_raw_chunk(&self, id: &[usize], bytes: Vec<u8>) -> Result<ZArray, CharizarrError> {
+        let chunk = match self.codec_execution {
+            CodecExecution::Inline => decode_chunk(
+                &self.codec_registry,
+                &self.data_type_registry,
+                &self.metadata.codecs,
+                self.dtype(),
+                bytes,
+            )?,
+            execution => {
+                let codec_registry = self.codec_registry.clone();
+                let data_type_registry = self.data_type_registry.clone();
+                let codecs = self.metadata.codecs.clone();
+                let data_type = self.dtype().clone();
+                execution
+                    .run(move || {
+                        decode_chunk(&codec_registry, &data_type_registry, &codecs, &data_type, bytes)
+                    })
+                    .await?
+            }
+        };
+
+        let chunk_shape = self.chunk_shape();
+        let len = chunk.shape().iter().product::<usize>();
+        if len != chunk_shape.iter().product::<usize>() {
+            return Err(CharizarrError::ArrayError(format!(
+                "Chunk {id:?} decoded to {len} values but the chunk shape is {chunk_shape:?}"
+            )));
+        }
+        Ok(chunk.reshape(&chunk_shape))
+    }
+
+    /// Decode a raw chunk to the native bytes of its elements on the codec
+    /// thread pool. Chunks whose codecs or data type cannot decode to bytes
+    /// are decoded to an array instead.
+    async fn decode_chunk_bytes(
+        &self,
+        id: &[usize],
+        bytes: Vec<u8>,
+        len: usize,
+        execution: CodecExecution,
+    ) -> Result<Result<Vec<u8>, ZArray>, CharizarrError> {
+        let codec_registry = self.codec_registry.clone();
+        let data_type_registry = self.data_type_registry.clone();
+        let codecs = self.metadata.codecs.clone();
+        let data_type = self.dtype().clone();
+        let decoded = execution
+            .run(move || {
+                let mut out = vec![0u8; len];
+                let decoded =
+                    decode_chunk_into(&codec_registry, &data_type_registry, &codecs, &data_type, &bytes, &mut out)?;
+                Ok(if decoded { Ok(out) } else { Err(bytes) })
+            })
+            .await?;
+        match decoded {
+            Ok(out) => Ok(Ok(out)),
+            Err(bytes) => self.decode_raw_chunk(id, bytes).await.map(Err),
+        }
+    }
+
+    /// Get a chunk from the store, or a chunk of the fill value if it has not
+    /// been written yet
+    async fn get_chunk_or_fill(&self, id: &[usize]) -> Result<ZArray, CharizarrError> {
+        match self.get_chunk(id).await {
+            Err(CharizarrError::KeyNotFoundError(_)) => self.data_type_registry.full(
+                self.dtype(),
+                &self.chunk_shape(),
+                &self.metadata.fill_value,
+            ),
+            result => result,
+        }
+    }
+
+    /// Erase a chunk from the store, so it reads as the fill value. Erasing a
+    /// chunk that has not been written does nothing.
+    pub async fn erase_chunk(&self, id: &[usize]) -> Result<(), CharizarrError> {
+        match self.store.erase(&self.get_chunk_key(id)).await {
+            Err(CharizarrError::KeyNotFoundError(_)) => Ok(()),
+            result => result,
+        }
+    }
+
+    /// Erase the given chunks from the store, a limited number at a time
+    pub async fn erase_chunks(&self, ids: &[Vec<usize>]) -> Result<(), CharizarrError> {
+        stream::iter(ids.iter().map(|id| self.erase_chunk(id)))
+            .buffer_unordered(self.concurrency)
+            .try_collect()
+            .await
     }
 
     /// Set a raw chunk in the store, without encoding it
@@ -171,16 +313,68 @@ where
         self.store.set(&chunk_path, data).await
     }
 
-    /// Set a chunk in the store, encoding it according to the array's metadata
+    /// Set a chunk in the store, encoding it according to the array's metadata.
+    /// Chunks on the edge of the array may be given clipped to the array
+    /// bounds, and are padded with the fill value before encoding.
     pub async fn set_chunk(&self, key: &[usize], chunk: &ZArray) -> Result<(), CharizarrError> {
-        let data_type = self.dtype();
-        let data = encode_chunk(
-            &self.codec_registry,
-            &self.metadata.codecs,
-            data_type,
-            chunk,
-        )?;
-        self.set_raw_chunk(&key, &data).await
+        self.store_chunk(key, Cow::Borrowed(chunk)).await
+    }
+
+    /// Pad and encode a chunk, then write it to the store. Owned chunks can be
+    /// moved to the codec thread pool without copying them.
+    async fn store_chunk(&self, key: &[usize], chunk: Cow<'_, ZArray>) -> Result<(), CharizarrError> {
+        let data = self.encode_full_chunk(key, chunk).await?;
+        self.set_raw_chunk(key, &data).await
+    }
+
+    /// Pad a chunk clipped to the array bounds with the fill value, then
+    /// encode it according to the array's metadata
+    async fn encode_full_chunk(&self, key: &[usize], chunk: Cow<'_, ZArray>) -> Result<Vec<u8>, CharizarrError> {
+        let chunk_shape = self.chunk_shape();
+        let chunk = if chunk.shape() == chunk_shape.as_slice() {
+            chunk
+        } else if chunk.shape() == self.clipped_chunk_shape(key).as_slice() {
+            let mut padded = self.data_type_registry.full(
+                self.dtype(),
+                &chunk_shape,
+                &self.metadata.fill_value,
+            )?;
+            let region = chunk.shape().iter().map(|&len| Slice::from(0..len)).collect::<Vec<_>>();
+            let projection = ChunkProjection {
+                chunk_coords: key.to_vec(),
+                chunk_sel: region.clone(),
+                out_sel: region,
+            };
+            padded.set(&projection, &chunk)?;
+            Cow::Owned(padded)
+        } else {
+            return Err(CharizarrError::ArrayError(format!(
+                "Chunk {key:?} has shape {:?} but the chunk shape is {chunk_shape:?}",
+                chunk.shape()
+            )));
+        };
+
+        match self.codec_execution {
+            CodecExecution::Inline => encode_chunk(
+                &self.codec_registry,
+                &self.data_type_registry,
+                &self.metadata.codecs,
+                self.dtype(),
+                &chunk,
+            ),
+            execution => {
+                let codec_registry = self.codec_registry.clone();
+                let data_type_registry = self.data_type_registry.clone();
+                let codecs = self.metadata.codecs.clone();
+                let data_type = self.dtype().clone();
+                let chunk = chunk.into_owned();
+                execution
+                    .run(move || {
+                        encode_chunk(&codec_registry, &data_type_registry, &codecs, &data_type, &chunk)
+                    })
+                    .await
+            }
+        }
     }
 
     /// The data type of the array
@@ -188,6 +382,17 @@ where
         &self.metadata.data_type
     }
 
+    /// The data type of the arrays returned when reading, which differs from
+    /// the metadata data type for registered extension data types
+    pub fn array_data_type(&self) -> Result<DataType, CharizarrError> {
+        self.data_type_registry.array_data_type(self.dtype())
+    }
+
+    /// Convert into a typed array, checking the data type once
+    pub fn typed<E: Element>(self) -> Result<TypedArray<'a, T, E>, CharizarrError> {
+        TypedArray::new(self)
+    }
+
     /// Get the shape of the entire array
     pub fn shape(&self) -> Vec<usize> {
         self.metadata.shape.to_vec()
@@ -207,91 +412,756 @@ where
             .collect::<Vec<usize>>()
     }
 
+    /// The number of chunks along each dimension of the array
+    pub fn chunk_grid_shape(&self) -> Vec<usize> {
+        self.metadata
+            .shape
+            .iter()
+            .zip(self.chunk_shape())
+            .map(|(len, chunk_len)| len.div_ceil(chunk_len))
+            .collect()
+    }
+
+    /// The shape of a chunk clipped to the bounds of the array, which is
+    /// smaller than the chunk shape for chunks on the trailing edge
+    pub fn clipped_chunk_shape(&self, id: &[usize]) -> Vec<usize> {
+        self.metadata
+            .shape
+            .iter()
+            .zip(self.chunk_shape())
+            .zip(id)
+            .map(|((len, chunk_len), i)| chunk_len.min(len.saturating_sub(i * chunk_len)))
+            .collect()
+    }
+
     /// Slice the array according to the given index ranges, asynchronously
     /// reading slices from the store and decoding them, then concatenating
     /// into the correct indices
     ///
     /// This should use Index but async assosciated types are not yet stable
-    ///
-    /// Also maybe should use ndarray slice or sliceinfo as primitive
     pub async fn get(&self, index: Option<Vec<Range<usize>>>) -> Result<ZArray, CharizarrError> {
+        let selection = self.range_selection(index)?;
+        self.get_basic_selection(selection).await
+    }
+
+    /// Read a selection of slices and integer indices from the array, such as
+    /// `s![1..10;2, -1]`. Integer indices drop the dimension from the result.
+    pub async fn get_basic_selection(
+        &self,
+        selection: impl Into<BasicSelection>,
+    ) -> Result<ZArray, CharizarrError> {
         let array_shape = self.shape();
+        let selection = selection.into().resolve(&array_shape)?;
 
-        let index = index.unwrap_or_else(|| {
-            array_shape
-                .iter()
-                .map(|&s| 0..s)
-                .collect::<Vec<Range<usize>>>()
-        });
+        let indexer = BasicIndexIterator::new(array_shape, self.chunk_shape(), selection.dims);
+        let out_shape = indexer.shape.clone();
+        let out_array = self.read_projections(indexer, &out_shape).await?;
+        Ok(out_array.reshape(&selection.shape))
+    }
 
-        let out_shape = index
-            .iter()
-            .map(|r| r.end - r.start)
-            .collect::<Vec<usize>>();
-        let mut out_array = ZArray::zeros(self.dtype(), &out_shape)?;
-
-        // Gather all of the chunks, create futures for fetching chunk data
-        let chunks = BasicIndexIterator::new(array_shape, self.chunk_shape(), index).map(
-            |chunk_info| async {
-                self.get_chunk(&chunk_info.chunk_coords)
-                    .await
-                    .map(|chunk| (chunk_info, chunk))
-            },
-        );
+    /// Read a basic selection of the array into a caller provided buffer with
+    /// the shape of the selection, so repeated reads can reuse the buffer.
+    /// Chunks entirely covered by the selection are decoded straight into the
+    /// buffer when the codecs allow it, on the calling task.
+    pub async fn get_into<E: Element>(
+        &self,
+        selection: impl Into<BasicSelection>,
+        out: &mut ArrayViewMutD<'_, E>,
+    ) -> Result<(), CharizarrError> {
+        let data_type = self.array_data_type()?;
+        if !E::matches(&data_type) {
+            return Err(CharizarrError::TypeError(format!(
+                "{:?}, the array has data type {}",
+                E::data_type(),
+                data_type
+            )));
+        }
+
+        let array_shape = self.shape();
+        let selection = selection.into().resolve(&array_shape)?;
+        if out.shape() != selection.shape.as_slice() {
+            return Err(CharizarrError::ArrayError(format!(
+                "Output has shape {:?} but the selection has shape {:?}",
+                out.shape(),
+                selection.shape
+            )));
+        }
+
+        let indexer = BasicIndexIterator::new(array_shape, self.chunk_shape(), selection.dims);
+        let indexer_shape = indexer.shape.clone();
+        let mut out = if out.shape() == indexer_shape.as_slice() {
+            out.view_mut()
+        } else {
+            // Restore the indexed dimensions so the output lines up with the chunks
+            out.view_mut().into_shape(indexer_shape).map_err(|_| {
+                CharizarrError::ArrayError(
+                    "Output must be contiguous when the selection drops or adds dimensions"
+                        .to_string(),
+                )
+            })?
+        };
+
+        let fill_value = self
+            .data_type_registry
+            .full(self.dtype(), &[1], &self.metadata.fill_value)
+            .and_then(E::from_zarray)?
+            .into_raw_vec()
+            .remove(0);
+
+        // Chunks that fill a contiguous region of the output are decoded
+        // straight to bytes, in place when decoding inline or into a buffer on
+        // the codec thread pool otherwise. The rest are decoded like any other
+        // read.
+        enum Fetched {
+            Raw(Vec<u8>),
+            Bytes(Vec<u8>),
+            Decoded(ZArray),
+            Missing,
+        }
+        let chunk_shape = self.chunk_shape();
+        let chunk_bytes = chunk_shape.iter().product::<usize>() * std::mem::size_of::<E>();
+        let plain = E::as_bytes_mut(&mut []).is_some();
+        let chunks = indexer
+            .map(|chunk_info| {
+                let in_place = plain
+                    && chunk_info.is_complete(&chunk_shape)
+                    && out
+                        .slice_each_axis(|a| chunk_info.out_sel[a.axis.0])
+                        .is_standard_layout();
+                async move {
+                    let id = &chunk_info.chunk_coords;
+                    let chunk = match (in_place, self.codec_execution) {
+                        (false, _) => self.get_chunk(id).await.map(Fetched::Decoded),
+                        (true, CodecExecution::Inline) => self.get_raw_chunk(id).await.map(Fetched::Raw),
+                        (true, execution) => match self.get_raw_chunk(id).await {
+                            Ok(bytes) => self
+                                .decode_chunk_bytes(id, bytes, chunk_bytes, execution)
+                                .await
+                                .map(|chunk| chunk.map_or_else(Fetched::Decoded, Fetched::Bytes)),
+                            Err(e) => Err(e),
+                        },
+                    };
+                    match chunk {
+                        Err(CharizarrError::KeyNotFoundError(_)) => Ok((chunk_info, Fetched::Missing)),
+                        chunk => chunk.map(|chunk| (chunk_info, chunk)),
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut chunks = stream::iter(chunks).buffer_unordered(self.concurrency);
+        while let Some((chunk_info, chunk)) = chunks.try_next().await? {
+            let mut region = out.slice_each_axis_mut(|a| chunk_info.out_sel[a.axis.0]);
+            let chunk = match chunk {
+                Fetched::Missing => {
+                    region.fill(fill_value.clone());
+                    continue;
+                }
+                Fetched::Bytes(bytes) => {
+                    let out_bytes = region
+                        .as_slice_mut()
+                        .and_then(E::as_bytes_mut)
+                        .expect("In place chunks are contiguous plain elements");
+                    out_bytes.copy_from_slice(&bytes);
+                    continue;
+                }
+                Fetched::Raw(bytes) => {
+                    let decoded = match region.as_slice_mut().and_then(E::as_bytes_mut) {
+                        Some(out_bytes) => decode_chunk_into(
+                            &self.codec_registry,
+                            &self.data_type_registry,
+                            &self.metadata.codecs,
+                            self.dtype(),
+                            &bytes,
+                            out_bytes,
+                        )?,
+                        None => false,
+                    };
+                    if decoded {
+                        continue;
+                    }
+                    self.decode_raw_chunk(&chunk_info.chunk_coords, bytes).await?
+                }
+                Fetched::Decoded(chunk) => chunk,
+            };
+
+            let chunk = E::from_zarray(chunk)?;
+            region.assign(&chunk.slice_each_axis(|a| chunk_info.chunk_sel[a.axis.0]));
+        }
+
+        Ok(())
+    }
+
+    /// Read an orthogonal selection from the array, where each dimension is
+    /// selected by a slice, an index, a list of indices or a boolean mask.
+    /// Each chunk holding selected values is only read once.
+    pub async fn get_orthogonal_selection(
+        &self,
+        selection: impl Into<OrthogonalSelection>,
+    ) -> Result<ZArray, CharizarrError> {
+        let selection = selection.into().resolve(&self.shape())?;
+
+        let indexer = OrthogonalIndexIterator::new(self.chunk_shape(), &selection.dims);
+        let out_shape = indexer.shape.clone();
+        let out_array = self.read_projections(indexer, &out_shape).await?;
+        Ok(out_array.reshape(&selection.shape))
+    }
+
+    /// Read the values at individual points of the array, returning a one
+    /// dimensional array in the order of the points. The chunks holding the
+    /// points are fetched concurrently, each only once.
+    pub async fn get_coordinate_selection(
+        &self,
+        selection: impl Into<CoordinateSelection>,
+    ) -> Result<ZArray, CharizarrError> {
+        let coords = selection.into().resolve(&self.shape())?;
+
+        let indexer = CoordinateIndexIterator::new(self.chunk_shape(), &coords);
+        let out_shape = indexer.shape.clone();
+        self.read_projections(indexer, &out_shape).await
+    }
 
-        // Trigger the fetch on all of the chunks
-        let chunks = try_join_all(chunks).await?;
+    /// Read the values where a boolean mask of the same shape as the array is
+    /// true, returning a one dimensional array in row major order. Chunks
+    /// where the mask is entirely false are not read.
+    pub async fn get_mask_selection(&self, mask: &ZArray) -> Result<ZArray, CharizarrError> {
+        let selection = self.mask_selection(mask)?;
+        self.get_coordinate_selection(selection).await
+    }
 
-        // Insert the chunks into the correct place in the output array
-        chunks.iter().for_each(|(chunk_info, chunk)| {
-            // TODO: Handle error
-            let _ = out_array.set(&chunk_info, chunk);
+    /// Stream the chunks holding a selection of the array, without reading the
+    /// whole selection into memory. Up to `prefetch` chunks are read ahead of
+    /// the consumer. Each item holds the projection of the chunk and the part
+    /// of the chunk within the selection, which belongs at the output
+    /// selection of the projection. Integer indices select a length of one
+    /// rather than dropping the dimension.
+    pub fn iter_chunks(
+        &self,
+        selection: impl Into<BasicSelection>,
+        prefetch: usize,
+        order: ChunkOrder,
+    ) -> Result<
+        impl Stream<Item = Result<(ChunkProjection, ZArray), CharizarrError>> + '_,
+        CharizarrError,
+    > {
+        let array_shape = self.shape();
+        let selection = selection.into().resolve(&array_shape)?;
+        let indexer = BasicIndexIterator::new(array_shape, self.chunk_shape(), selection.dims);
+
+        let chunks = stream::iter(indexer).map(move |chunk_info| async move {
+            let chunk = self.get_chunk_or_fill(&chunk_info.chunk_coords).await?;
+            let shape = chunk_info
+                .out_sel
+                .iter()
+                .map(|sel| (sel.end.unwrap_or(sel.start) - sel.start) as usize)
+                .collect::<Vec<_>>();
+            let projection = ChunkProjection {
+                chunk_coords: chunk_info.chunk_coords.clone(),
+                chunk_sel: chunk_info.chunk_sel.clone(),
+                out_sel: shape.iter().map(|&len| Slice::from(0..len)).collect(),
+            };
+
+            let mut data = self
+                .data_type_registry
+                .full(self.dtype(), &shape, &self.metadata.fill_value)?;
+            data.set(&projection, &chunk)?;
+            Ok((chunk_info, data))
         });
 
-        Ok(out_array)
+        let prefetch = prefetch.max(1);
+        Ok(match order {
+            ChunkOrder::Ordered => chunks.buffered(prefetch).left_stream(),
+            ChunkOrder::Unordered => chunks.buffer_unordered(prefetch).right_stream(),
+        })
     }
 
-    /// Slice the array according to the given index ranges, asynchronously
-    /// reading slices from the store and decoding them, then concatenating
-    /// into the correct indices
+    /// Write the values to the given index ranges of the array, reading the
+    /// existing chunks and overwriting the selected values
     ///
     /// This should use Index but async assosciated types are not yet stable
-    ///
-    /// Also maybe should use ndarray slice or sliceinfo as primitive
     pub async fn set(
         &self,
         index: Option<Vec<Range<usize>>>,
         value: &ZArray,
+    ) -> Result<(), CharizarrError> {
+        let selection = self.range_selection(index)?;
+        self.set_basic_selection(selection, value).await
+    }
+
+    /// Write the values to a selection of slices and integer indices of the
+    /// array. The values must have the shape of the selection.
+    pub async fn set_basic_selection(
+        &self,
+        selection: impl Into<BasicSelection>,
+        value: &ZArray,
     ) -> Result<(), CharizarrError> {
         let array_shape = self.shape();
+        let selection = selection.into().resolve(&array_shape)?;
+        self.check_value(value, &selection.shape)?;
+
+        let indexer = BasicIndexIterator::new(array_shape, self.chunk_shape(), selection.dims);
+
+        // Restore the indexed dimensions so the values line up with the chunks
+        let value = if value.shape() == indexer.shape.as_slice() {
+            Cow::Borrowed(value)
+        } else {
+            Cow::Owned(value.clone().reshape(&indexer.shape))
+        };
+
+        self.write_projections(indexer, &value).await
+    }
+
+    /// Write the values to an orthogonal selection of the array. The values
+    /// must have the shape of the selection.
+    pub async fn set_orthogonal_selection(
+        &self,
+        selection: impl Into<OrthogonalSelection>,
+        value: &ZArray,
+    ) -> Result<(), CharizarrError> {
+        let selection = selection.into().resolve(&self.shape())?;
+        self.check_value(value, &selection.shape)?;
+
+        let indexer = OrthogonalIndexIterator::new(self.chunk_shape(), &selection.dims);
+        let value = if value.shape() == indexer.shape.as_slice() {
+            Cow::Borrowed(value)
+        } else {
+            Cow::Owned(value.clone().reshape(&indexer.shape))
+        };
+
+        self.write_projections(indexer, &value).await
+    }
+
+    /// Write a one dimensional array of values to individual points of the
+    /// array, in the order of the points
+    pub async fn set_coordinate_selection(
+        &self,
+        selection: impl Into<CoordinateSelection>,
+        value: &ZArray,
+    ) -> Result<(), CharizarrError> {
+        let coords = selection.into().resolve(&self.shape())?;
+
+        let indexer = CoordinateIndexIterator::new(self.chunk_shape(), &coords);
+        self.check_value(value, &indexer.shape)?;
+
+        self.write_projections(indexer, value).await
+    }
+
+    /// Write a one dimensional array of values, in row major order, to where
+    /// a boolean mask of the same shape as the array is true. Chunks where the
+    /// mask is entirely false are not read or written.
+    pub async fn set_mask_selection(
+        &self,
+        mask: &ZArray,
+        value: &ZArray,
+    ) -> Result<(), CharizarrError> {
+        let selection = self.mask_selection(mask)?;
+        self.set_coordinate_selection(selection, value).await
+    }
+
+    /// Change the shape of the array, keeping the chunk shape. Chunks outside
+    /// of a shrunk shape are erased, and the part of edge chunks beyond the
+    /// new bounds is reset to the fill value so growing the array again reads
+    /// the fill value there.
+    pub async fn resize(&mut self, new_shape: Vec<usize>) -> Result<(), CharizarrError> {
+        let old_shape = self.shape();
+        if new_shape.len() != old_shape.len() {
+            return Err(CharizarrError::ArrayError(format!(
+                "Cannot resize an array with {} dimensions to shape {new_shape:?}",
+                old_shape.len()
+            )));
+        }
+
         let chunk_shape = self.chunk_shape();
+        let old_grid = self.chunk_grid_shape();
+        let new_grid = new_shape
+            .iter()
+            .zip(&chunk_shape)
+            .map(|(len, chunk_len)| len.div_ceil(*chunk_len))
+            .collect::<Vec<_>>();
+        // Axes where the last chunk inside the new shape is cut short
+        let clipped_axes = new_shape
+            .iter()
+            .zip(&old_shape)
+            .zip(&chunk_shape)
+            .map(|((&new_len, &old_len), &chunk_len)| new_len < old_len && new_len % chunk_len != 0)
+            .collect::<Vec<_>>();
 
-        let index = index.unwrap_or_else(|| {
-            array_shape
+        // Chunks outside of the new grid, split into disjoint slabs along each
+        // axis that shrinks
+        let mut outside = Vec::new();
+        for axis in (0..old_grid.len()).filter(|&a| new_grid[a] < old_grid[a]) {
+            let ranges = (0..old_grid.len()).map(|a| match a.cmp(&axis) {
+                Ordering::Less => 0..new_grid[a].min(old_grid[a]),
+                Ordering::Equal => new_grid[a]..old_grid[a],
+                Ordering::Greater => 0..old_grid[a],
+            });
+            outside.extend(chunk_ids(ranges));
+        }
+
+        // The last row of chunks inside the new grid along each axis that is
+        // cut short, skipping rows already covered by an earlier axis
+        let mut clipped = Vec::new();
+        for axis in (0..new_grid.len()).filter(|&a| clipped_axes[a]) {
+            let ranges = (0..new_grid.len()).map(|a| match a.cmp(&axis) {
+                Ordering::Less if clipped_axes[a] => 0..new_grid[a] - 1,
+                Ordering::Equal => new_grid[a] - 1..new_grid[a],
+                _ => 0..new_grid[a],
+            });
+            clipped.extend(chunk_ids(ranges));
+        }
+
+        // Clean up the chunks before writing the metadata, so a failure never
+        // leaves a shrunk shape with stale data beyond it
+        self.erase_chunks(&outside).await?;
+        stream::iter(clipped.iter().map(|id| self.clear_clipped_chunk(id, &new_shape)))
+            .buffer_unordered(self.concurrency)
+            .try_collect::<()>()
+            .await?;
+
+        self.metadata.shape = new_shape;
+        self.write_metadata().await
+    }
+
+    /// Reset the part of a stored edge chunk beyond the given array shape to
+    /// the fill value
+    async fn clear_clipped_chunk(&self, id: &[usize], shape: &[usize]) -> Result<(), CharizarrError> {
+        let chunk = match self.get_chunk(id).await {
+            Err(CharizarrError::KeyNotFoundError(_)) => return Ok(()),
+            result => result?,
+        };
+        let chunk_shape = self.chunk_shape();
+        let mut cleared = self.data_type_registry.full(
+            self.dtype(),
+            &chunk_shape,
+            &self.metadata.fill_value,
+        )?;
+        let region = shape
+            .iter()
+            .zip(&chunk_shape)
+            .zip(id)
+            .map(|((len, chunk_len), i)| Slice::from(0..(*chunk_len).min(len - i * chunk_len)))
+            .collect::<Vec<_>>();
+        let projection = ChunkProjection {
+            chunk_coords: id.to_vec(),
+            chunk_sel: region.clone(),
+            out_sel: region,
+        };
+        cleared.set(&projection, &chunk)?;
+        self.store_chunk(id, Cow::Owned(cleared)).await
+    }
+
+    /// Grow the array along an axis and write the values to the new region.
+    /// The values must match the shape of the array on every other axis.
+    pub async fn append(&mut self, axis: usize, value: &ZArray) -> Result<(), CharizarrError> {
+        let mut shape = self.shape();
+        if axis >= shape.len() {
+            return Err(CharizarrError::ArrayError(format!(
+                "Axis {axis} is out of bounds for an array with {} dimensions",
+                shape.len()
+            )));
+        }
+        let mismatched = value.shape().len() != shape.len()
+            || value.shape().iter().zip(&shape).enumerate().any(|(a, (v, s))| a != axis && v != s);
+        if mismatched {
+            return Err(CharizarrError::ArrayError(format!(
+                "Cannot append values with shape {:?} to an array with shape {shape:?} along axis {axis}",
+                value.shape()
+            )));
+        }
+        self.check_value(value, value.shape())?;
+
+        let start = shape[axis];
+        shape[axis] += value.shape()[axis];
+        let region = shape
+            .iter()
+            .enumerate()
+            .map(|(a, &len)| if a == axis { start..len } else { 0..len })
+            .collect::<Vec<_>>();
+        self.resize(shape).await?;
+        self.set_basic_selection(region, value).await
+    }
+
+    /// Copy the array to a path in another store. Chunks are copied without
+    /// decoding them unless the options give different codecs, in which case
+    /// each chunk is decoded and encoded again. See `CopyOptions::with_resume`
+    /// for which chunks are skipped when resuming. The metadata is written
+    /// last.
+    pub async fn copy_to<D>(
+        &self,
+        store: &D,
+        path: Option<String>,
+        options: &CopyOptions,
+    ) -> Result<CopyReport, CharizarrError>
+    where
+        D: ReadableStore + ListableStore + WriteableStore,
+    {
+        let path = path.map_or_else(|| "".to_string(), |p| format!("{p}/"));
+        let codecs = match &options.codecs {
+            Some(codecs) if *codecs != self.metadata.codecs => codecs.clone(),
+            _ => return copy_prefix(self.store, &self.path, store, &path, options).await,
+        };
+
+        let mut metadata = self.metadata.clone();
+        metadata.codecs = codecs;
+        let dst = Array {
+            store,
+            codec_registry: self.codec_registry.clone(),
+            data_type_registry: self.data_type_registry.clone(),
+            concurrency: options.concurrency,
+            codec_execution: self.codec_execution,
+            metadata,
+            path,
+        };
+
+        let ids = chunk_ids(self.chunk_grid_shape().into_iter().map(|n| 0..n));
+        let copied: Vec<Option<bool>> = stream::iter(ids.iter().map(|id| async {
+            let chunk = match self.get_chunk(id).await {
+                Err(CharizarrError::KeyNotFoundError(_)) => return Ok(None),
+                result => result?,
+            };
+            let data = dst.encode_full_chunk(id, Cow::Owned(chunk)).await?;
+            if options.resume && dst_has_size(dst.store, &dst.get_chunk_key(id), data.len()).await? {
+                return Ok(Some(false));
+            }
+            dst.set_raw_chunk(id, &data).await.map(|_| Some(true))
+        }))
+        .buffer_unordered(options.concurrency)
+        .try_collect()
+        .await?;
+
+        dst.write_metadata().await?;
+        Ok(CopyReport::from_copied(copied.into_iter().flatten().chain([true])))
+    }
+
+    /// Copy the array to a path in another store with a different chunk
+    /// shape. The array is read in blocks, and the number of chunks read or
+    /// written at a time is limited, to fit in the memory budget of the
+    /// options. Each new chunk is written once. Data types without a fixed
+    /// size, such as variable length strings, need an element size estimate
+    /// in the options. The metadata is written last.
+    pub async fn rechunk_to<'b, D>(
+        &self,
+        store: &'b D,
+        path: Option<String>,
+        chunk_shape: Vec<usize>,
+        options: &RechunkOptions,
+    ) -> Result<Array<'b, D>, CharizarrError>
+    where
+        D: ReadableStore + ListableStore + WriteableStore,
+    {
+        let shape = self.shape();
+        if chunk_shape.len() != shape.len() || chunk_shape.contains(&0) {
+            return Err(CharizarrError::ArrayError(format!(
+                "Cannot rechunk an array with shape {shape:?} to chunk shape {chunk_shape:?}"
+            )));
+        }
+
+        let mut metadata = self.metadata.clone();
+        metadata.chunk_grid = Extension {
+            name: "regular".to_string(),
+            configuration: serde_json::json!({ "chunk_shape": chunk_shape }),
+        };
+        if let Some(codecs) = &options.codecs {
+            metadata.codecs = codecs.clone();
+        }
+
+        let element_size = match self.data_type_registry.size(self.dtype()) {
+            Some(size) => size,
+            None => options.element_size.ok_or_else(|| {
+                CharizarrError::ArrayError(format!(
+                    "Elements of data type {} have no fixed size, an element size estimate is needed to rechunk",
+                    self.dtype()
+                ))
+            })?,
+        };
+        // Half of the budget holds a block, the other half the chunks decoded
+        // or encoded at the same time, which limits the concurrency
+        let budget = options.max_mem / 2;
+        let concurrency = |chunk_shape: &[usize]| {
+            let chunk_bytes = chunk_shape.iter().product::<usize>() * element_size;
+            (budget / chunk_bytes.max(1)).clamp(1, self.concurrency)
+        };
+
+        let src = Array {
+            store: self.store,
+            codec_registry: self.codec_registry.clone(),
+            data_type_registry: self.data_type_registry.clone(),
+            concurrency: concurrency(&self.chunk_shape()),
+            codec_execution: self.codec_execution,
+            metadata: self.metadata.clone(),
+            path: self.path.clone(),
+        };
+        let dst = Array {
+            store,
+            codec_registry: self.codec_registry.clone(),
+            data_type_registry: self.data_type_registry.clone(),
+            concurrency: concurrency(&chunk_shape),
+            codec_execution: self.codec_execution,
+            metadata,
+            path: path.map_or_else(|| "".to_string(), |p| format!("{p}/")),
+        };
+
+        let block = block_shape(&shape, &self.chunk_shape(), &chunk_shape, element_size, budget);
+        let block_ids = chunk_ids(shape.iter().zip(&block).map(|(len, block_len)| 0..len.div_ceil(*block_len)));
+        for id in block_ids {
+            let region = id
                 .iter()
-                .map(|&s| 0..s)
-                .collect::<Vec<Range<usize>>>()
+                .zip(&shape)
+                .zip(&block)
+                .map(|((i, &len), &block_len)| i * block_len..len.min((i + 1) * block_len))
+                .collect::<Vec<_>>();
+            let data = src.get(Some(region.clone())).await?;
+            dst.set(Some(region), &data).await?;
+        }
+
+        dst.write_metadata().await?;
+        Ok(dst.with_concurrency(self.concurrency))
+    }
+
+    /// Erase the array metadata and every chunk from the store. With
+    /// `dry_run` nothing is erased. Returns the keys that were, or would be,
+    /// erased.
+    pub async fn delete(&self, dry_run: bool) -> Result<Vec<String>, CharizarrError> {
+        erase_node(self.store, &self.path, dry_run).await
+    }
+
+    /// Check that index ranges select a region within the array, with a range
+    /// for every dimension
+    fn range_selection(
+        &self,
+        index: Option<Vec<Range<usize>>>,
+    ) -> Result<BasicSelection, CharizarrError> {
+        let Some(index) = index else {
+            return Ok(BasicSelection::default());
+        };
+
+        let shape = &self.metadata.shape;
+        if index.len() != shape.len() {
+            return Err(CharizarrError::ArrayError(format!(
+                "Selection has {} dimensions but the array has {}",
+                index.len(),
+                shape.len()
+            )));
+        }
+        for (axis, (range, len)) in index.iter().zip(shape).enumerate() {
+            if range.start > range.end || range.end > *len {
+                return Err(CharizarrError::ArrayError(format!(
+                    "Range {range:?} is out of bounds for axis {axis} with length {len}"
+                )));
+            }
+        }
+
+        Ok(BasicSelection::from(index))
+    }
+
+    /// Check that values written to the array have the shape of the selection
+    /// and the data type of the array
+    fn check_value(&self, value: &ZArray, shape: &[usize]) -> Result<(), CharizarrError> {
+        if value.shape() != shape {
+            return Err(CharizarrError::ArrayError(format!(
+                "Value has shape {:?} but the selection has shape {:?}",
+                value.shape(),
+                shape
+            )));
+        }
+
+        // Compare against an empty array so extension configurations are
+        // normalized the same way
+        let data_type = self.data_type_registry.zeros(self.dtype(), &[0])?.data_type();
+        if value.data_type() != data_type {
+            return Err(CharizarrError::TypeError(format!(
+                "{data_type}, the value has data type {}",
+                value.data_type()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Convert a boolean mask over the entire array to the selected points
+    fn mask_selection(&self, mask: &ZArray) -> Result<CoordinateSelection, CharizarrError> {
+        let ZArray::Bool(mask) = mask else {
+            return Err(CharizarrError::TypeError(format!(
+                "Mask must be a boolean array, got {}",
+                mask.data_type()
+            )));
+        };
+        if mask.shape() != self.metadata.shape.as_slice() {
+            return Err(CharizarrError::ArrayError(format!(
+                "Mask has shape {:?} but the array has shape {:?}",
+                mask.shape(),
+                self.metadata.shape
+            )));
+        }
+
+        Ok(CoordinateSelection::from_mask(mask.view()))
+    }
+
+    /// Read the chunks of each projection concurrently and copy the selected
+    /// values into a new array of the given shape
+    async fn read_projections<P: Projection>(
+        &self,
+        projections: impl Iterator<Item = P>,
+        shape: &[usize],
+    ) -> Result<ZArray, CharizarrError> {
+        let mut out_array = self
+            .data_type_registry
+            .full(self.dtype(), shape, &self.metadata.fill_value)?;
+
+        // Gather all of the chunks, create futures for fetching chunk data.
+        // Chunks that have not been written hold the fill value.
+        let chunks = projections.map(|chunk_info| async {
+            self.get_chunk_or_fill(chunk_info.chunk_coords())
+                .await
+                .map(|chunk| (chunk_info, chunk))
         });
 
-        let chunks =
-            BasicIndexIterator::new(array_shape, chunk_shape, index).map(|chunk_info| async {
-                self.get_chunk(&chunk_info.chunk_coords)
-                    .await
-                    .map(|chunk| (chunk_info, chunk))
-            });
+        // Fetch a limited number of chunks at a time, inserting each chunk
+        // into the correct place in the output array as it arrives
+        let mut chunks = stream::iter(chunks).buffer_unordered(self.concurrency);
+        while let Some((chunk_info, chunk)) = chunks.try_next().await? {
+            out_array.set(&chunk_info, &chunk).map_err(|e| {
+                CharizarrError::ArrayError(format!(
+                    "Failed to read chunk {:?}: {e}",
+                    chunk_info.chunk_coords()
+                ))
+            })?;
+        }
 
-        // Trigger the fetch on all of the chunks, then overwrite the values
-        // in the chunks with the new values
-        let mut existing_chunks = try_join_all(chunks).await?;
-        let new_chunks = existing_chunks
-            .iter_mut()
-            .map(|(chunk_info, chunk)| async move {
-                chunk.set(&chunk_info, &value)?;
-                self.set_chunk(&chunk_info.chunk_coords, chunk).await
-            });
+        Ok(out_array)
+    }
 
-        // Trigger futures, wait for all to complete
-        try_join_all(new_chunks).await.map(|_| ())
+    /// Overwrite the selected values of the chunk of each projection and
+    /// write the chunks back to the store, concurrently. Chunks that are only
+    /// partially covered by the selection are read first, while chunks that
+    /// are entirely overwritten are encoded directly from the values.
+    async fn write_projections<P: Projection>(
+        &self,
+        projections: impl Iterator<Item = P>,
+        value: &ZArray,
+    ) -> Result<(), CharizarrError> {
+        let new_chunks = projections.map(|chunk_info| async move {
+            let id = chunk_info.chunk_coords();
+            let mut chunk = if chunk_info.is_complete(&self.clipped_chunk_shape(id)) {
+                self.data_type_registry.full(
+                    self.dtype(),
+                    &self.chunk_shape(),
+                    &self.metadata.fill_value,
+                )?
+            } else {
+                self.get_chunk_or_fill(id).await?
+            };
+
+            chunk.set(&chunk_info.swapped(), value).map_err(|e| {
+                CharizarrError::ArrayError(format!("Failed to write chunk {id:?}: {e}"))
+            })?;
+            self.store_chunk(id, Cow::Owned(chunk)).await
+        });
+
+        // Write a limited number of chunks at a time, wait for all to complete
+        stream::iter(new_chunks)
+            .buffer_unordered(self.concurrency)
+            .try_collect()
+            .await
     }
 
     /// Add an attribute to the group
@@ -343,6 +1213,16 @@ where
     }
 }
 
+/// Every chunk id in the product of the given ranges, in row major order. An
+/// array with no dimensions has a single chunk with an empty id.
+fn chunk_ids(ranges: impl IntoIterator<Item = Range<usize>>) -> Vec<Vec<usize>> {
+    let ranges = ranges.into_iter().collect::<Vec<_>>();
+    if ranges.is_empty() {
+        return vec![vec![]];
+    }
+    ranges.into_iter().multi_cartesian_product().collect()
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::json;
@@ -1,9 +1,100 @@
-use crate::{codec::Codec, codec_registry::CodecRegistry, error::CharizarrError, metadata::{DataType, Extension}, zarray::ZArray};
+use std::{borrow::Cow, sync::Arc};
 
+use serde_json::Value;
 
+use crate::{codec::{ByteToArrayCodec, Codec}, codec_registry::CodecRegistry, data_type_registry::DataTypeRegistry, error::CharizarrError, metadata::{DataType, Extension}, zarray::ZArray};
+
+/// Where the CPU bound work of encoding and decoding chunks runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CodecExecution {
+    /// On the task reading or writing the chunk, blocking the async executor
+    Inline,
+    /// On the tokio blocking thread pool, so chunks are decoded in parallel
+    /// while other chunks are fetched. Runs inline outside of a tokio runtime.
+    #[default]
+    SpawnBlocking,
+    /// On the global rayon thread pool
+    #[cfg(feature = "rayon")]
+    Rayon,
+}
+
+impl CodecExecution {
+    /// Run a codec task according to the execution strategy
+    pub async fn run<R, F>(self, task: F) -> Result<R, CharizarrError>
+    where
+        R: Send + 'static,
+        F: FnOnce() -> Result<R, CharizarrError> + Send + 'static,
+    {
+        match self {
+            CodecExecution::Inline => task(),
+            CodecExecution::SpawnBlocking => match tokio::runtime::Handle::try_current() {
+                Ok(handle) => handle.spawn_blocking(task).await.map_err(|e| {
+                    CharizarrError::CodecError(format!("Codec task failed: {e}"))
+                })?,
+                Err(_) => task(),
+            },
+            #[cfg(feature = "rayon")]
+            CodecExecution::Rayon => {
+                let (sender, receiver) = futures::channel::oneshot::channel();
+                rayon::spawn(move || {
+                    let _ = sender.send(task());
+                });
+                receiver.await.map_err(|_| {
+                    CharizarrError::CodecError("Codec task was cancelled".to_string())
+                })?
+            }
+        }
+    }
+}
+
+/// The data type the byte to array codec receives. Registered extension data
+/// types keep their own data type unless an array to array codec changed the
+/// array data type, so the `bytes` codec can use the extension's encoding.
+fn bta_data_type(
+    data_type_registry: &DataTypeRegistry,
+    data_type: &DataType,
+    encoded_data_type: &DataType,
+) -> Result<DataType, CharizarrError> {
+    if &data_type_registry.array_data_type(data_type)? == encoded_data_type {
+        Ok(data_type.clone())
+    } else {
+        Ok(encoded_data_type.clone())
+    }
+}
+
+fn bta_decode(
+    data_type_registry: &DataTypeRegistry,
+    codec: &Arc<dyn ByteToArrayCodec>,
+    data_type: &DataType,
+    config: &Value,
+    bytes: &[u8],
+) -> Result<ZArray, CharizarrError> {
+    match data_type_registry.resolve(data_type) {
+        Some((ext, ext_config)) if codec.resolve_name() == "bytes" => {
+            ext.decode_bytes(ext_config, config, bytes)
+        }
+        _ => codec.decode(&data_type_registry.array_data_type(data_type)?, config, bytes),
+    }
+}
+
+fn bta_encode(
+    data_type_registry: &DataTypeRegistry,
+    codec: &Arc<dyn ByteToArrayCodec>,
+    data_type: &DataType,
+    config: &Value,
+    arr: &ZArray,
+) -> Result<Vec<u8>, CharizarrError> {
+    match data_type_registry.resolve(data_type) {
+        Some((ext, ext_config)) if codec.resolve_name() == "bytes" => {
+            ext.encode_bytes(ext_config, config, arr)
+        }
+        _ => codec.encode(&data_type_registry.array_data_type(data_type)?, config, arr),
+    }
+}
 
 pub fn decode_chunk(
     codec_registry: &CodecRegistry,
+    data_type_registry: &DataTypeRegistry,
     codecs: &[Extension],
     data_type: &DataType,
     bytes: Vec<u8>,
@@ -24,10 +115,24 @@ pub fn decode_chunk(
         }
     });
 
+    // Resolve the data type each array to array codec receives when encoding,
+    // the codecs are stored in reverse so the data types are resolved in reverse
+    let mut ata_data_types = vec![];
+    let encoded_data_type =
+        ata_codecs
+            .iter()
+            .rev()
+            .try_fold(data_type_registry.array_data_type(data_type)?, |data_type, (codec, config)| {
+                let resolved = codec.resolve_data_type(&data_type, config)?;
+                ata_data_types.push(data_type);
+                Ok::<_, CharizarrError>(resolved)
+            })?;
+    ata_data_types.reverse();
+
     // byte to byte
     let bytes = btb_codecs.iter().try_fold(bytes, |bytes, codec| {
         let (codec, config) = codec;
-        codec.decode(data_type, config, &bytes)
+        codec.decode(&encoded_data_type, config, &bytes)
     })?;
 
     // byte to array
@@ -36,18 +141,61 @@ pub fn decode_chunk(
             "No ByteToArray codec found".to_string(),
         ));
     };
-    let arr = bta_codec.decode(data_type, bta_config, &bytes)?;
+    let bta_data_type = bta_data_type(data_type_registry, data_type, &encoded_data_type)?;
+    let arr = bta_decode(data_type_registry, bta_codec, &bta_data_type, bta_config, &bytes)?;
 
     // array to array
-    let arr = ata_codecs.iter().fold(arr, |arr, (codec, config)| {
-        codec.decode(data_type, config, &arr).unwrap()
-    });
+    let arr = ata_codecs.iter().zip(ata_data_types.iter()).try_fold(
+        arr,
+        |arr, ((codec, config), data_type)| codec.decode(data_type, config, &arr),
+    )?;
 
     Ok(arr)
 }
 
+/// Decode a chunk straight into the native bytes of the output array,
+/// skipping the allocation of a decoded chunk. Returns false without writing
+/// to the output when the codecs or the data type do not support it.
+pub fn decode_chunk_into(
+    codec_registry: &CodecRegistry,
+    data_type_registry: &DataTypeRegistry,
+    codecs: &[Extension],
+    data_type: &DataType,
+    bytes: &[u8],
+    out: &mut [u8],
+) -> Result<bool, CharizarrError> {
+    if data_type_registry.resolve(data_type).is_some() {
+        return Ok(false);
+    }
+
+    let mut btb_codecs = vec![];
+    let mut bta_codecs = vec![];
+    for codec in codecs.iter().rev() {
+        let config = &codec.configuration;
+        match codec_registry.get(&codec.name) {
+            Some(Codec::ByteToByte(codec)) => btb_codecs.push((codec, config)),
+            Some(Codec::ByteToArray(codec)) => bta_codecs.push((codec, config)),
+            // Array to array codecs change the layout of the decoded array
+            Some(Codec::ArrayToArray(_)) => return Ok(false),
+            None => {}
+        }
+    }
+
+    let Some((bta_codec, bta_config)) = bta_codecs.first() else {
+        return Err(CharizarrError::CodecError(
+            "No ByteToArray codec found".to_string(),
+        ));
+    };
+
+    let bytes = btb_codecs.iter().try_fold(Cow::Borrowed(bytes), |bytes, (codec, config)| {
+        codec.decode(data_type, config, &bytes).map(Cow::Owned)
+    })?;
+    bta_codec.decode_into(data_type, bta_config, &bytes, out)
+}
+
 pub fn encode_chunk(
     codec_registry: &CodecRegistry,
+    data_type_registry: &DataTypeRegistry,
     codecs: &[Extension],
     data_type: &DataType,
     arr: &ZArray,
@@ -69,21 +217,29 @@ pub fn encode_chunk(
     });
 
     // array to array
-    let new_arr = ata_codecs
-        .iter()
-        .try_fold(arr.clone(), |arr, (codec, config)| {
-            codec.encode(data_type, config, &arr)
-        })?;
+    let (new_arr, encoded_data_type) = ata_codecs.iter().try_fold(
+        (arr.clone(), data_type_registry.array_data_type(data_type)?),
+        |(arr, data_type), (codec, config)| {
+            let arr = codec.encode(&data_type, config, &arr)?;
+            let data_type = codec.resolve_data_type(&data_type, config)?;
+            Ok::<_, CharizarrError>((arr, data_type))
+        },
+    )?;
 
     // array to byte
-    let (bta_codec, bta_config) = bta_codecs.first().unwrap();
-    let bytes = bta_codec.encode(data_type, bta_config, &new_arr)?;
+    let Some((bta_codec, bta_config)) = bta_codecs.first() else {
+        return Err(CharizarrError::CodecError(
+            "No ByteToArray codec found".to_string(),
+        ));
+    };
+    let bta_data_type = bta_data_type(data_type_registry, data_type, &encoded_data_type)?;
+    let bytes = bta_encode(data_type_registry, bta_codec, &bta_data_type, bta_config, &new_arr)?;
 
     // byte to byte
     let bytes = btb_codecs
         .iter()
         .try_fold(bytes, |bytes, (codec, config)| {
-            codec.encode(data_type, config, &bytes)
+            codec.encode(&encoded_data_type, config, &bytes)
         })?;
 
     Ok(bytes)
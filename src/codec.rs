@@ -29,7 +29,7 @@ pub trait NamedCodec {
     fn resolve_name(&self) -> String;
 }
 
-pub trait ByteToArrayCodec: NamedCodec {
+pub trait ByteToArrayCodec: NamedCodec + Send + Sync {
     fn encode(
         &self,
         data_type: &DataType,
@@ -42,9 +42,32 @@ pub trait ByteToArrayCodec: NamedCodec {
         config: &Value,
         data: &[u8],
     ) -> Result<ZArray, CharizarrError>;
+
+    /// Decode straight into the bytes of an output array of the data type,
+    /// in native byte order. Returns false if the codec can not decode in
+    /// place, in which case `decode` is used instead.
+    fn decode_into(
+        &self,
+        _data_type: &DataType,
+        _config: &Value,
+        _data: &[u8],
+        _out: &mut [u8],
+    ) -> Result<bool, CharizarrError> {
+        Ok(false)
+    }
 }
 
-pub trait ArrayToArrayCodec: NamedCodec {
+pub trait ArrayToArrayCodec: NamedCodec + Send + Sync {
+    /// The data type of the array produced when encoding an array of the given
+    /// data type. This is the data type the next codec in the chain receives.
+    fn resolve_data_type(
+        &self,
+        data_type: &DataType,
+        _config: &Value,
+    ) -> Result<DataType, CharizarrError> {
+        Ok(data_type.clone())
+    }
+
     fn encode(
         &self,
         data_type: &DataType,
@@ -59,7 +82,7 @@ pub trait ArrayToArrayCodec: NamedCodec {
     ) -> Result<ZArray, CharizarrError>;
 }
 
-pub trait ByteToByteCodec: NamedCodec {
+pub trait ByteToByteCodec: NamedCodec + Send + Sync {
     fn encode(
         &self,
         data_type: &DataType,
@@ -1,6 +1,13 @@
 use std::{collections::HashMap, sync::Arc};
 
-use crate::{codec::Codec, codecs::bytes::BytesCodec};
+use crate::{
+    codec::Codec,
+    codecs::{
+        bytes::BytesCodec,
+        numcodecs::{BitRoundCodec, DeltaCodec, FixedScaleOffsetCodec, QuantizeCodec, ShuffleCodec},
+        vlen::{VLenBytesCodec, VLenUtf8Codec},
+    },
+};
 
 #[derive(Clone)]
 pub struct CodecRegistry {
@@ -10,9 +17,26 @@ pub struct CodecRegistry {
 impl Default for CodecRegistry {
     fn default() -> Self {
         let bytes_codec = Codec::ByteToArray(Arc::new(BytesCodec::new()));
+        let vlen_utf8_codec = Codec::ByteToArray(Arc::new(VLenUtf8Codec::new()));
+        let vlen_bytes_codec = Codec::ByteToArray(Arc::new(VLenBytesCodec::new()));
+
+        // The numcodecs compatible filters, which zarr-python writes under
+        // `numcodecs.*` codec names
+        let delta_codec = Codec::ArrayToArray(Arc::new(DeltaCodec::new()));
+        let fixed_scale_offset_codec = Codec::ArrayToArray(Arc::new(FixedScaleOffsetCodec::new()));
+        let quantize_codec = Codec::ArrayToArray(Arc::new(QuantizeCodec::new()));
+        let bit_round_codec = Codec::ArrayToArray(Arc::new(BitRoundCodec::new()));
+        let shuffle_codec = Codec::ByteToByte(Arc::new(ShuffleCodec::new()));
 
         let mut codecs = HashMap::new();
         codecs.insert(bytes_codec.name(), bytes_codec);
+        codecs.insert(vlen_utf8_codec.name(), vlen_utf8_codec);
+        codecs.insert(vlen_bytes_codec.name(), vlen_bytes_codec);
+        codecs.insert(delta_codec.name(), delta_codec);
+        codecs.insert(fixed_scale_offset_codec.name(), fixed_scale_offset_codec);
+        codecs.insert(quantize_codec.name(), quantize_codec);
+        codecs.insert(bit_round_codec.name(), bit_round_codec);
+        codecs.insert(shuffle_codec.name(), shuffle_codec);
 
         Self {
             codecs,
@@ -5,13 +5,14 @@ use serde_json::Value;
 use crate::{
     codec::{ByteToByteCodec, NamedCodec},
     error::CharizarrError,
-    metadata::DataType,
+    metadata::{DataType, Extension},
 };
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum BloscShuffle {
     NoShuffle,
+    #[default]
     Shuffle,
     BitShuffle,
 }
@@ -26,12 +27,13 @@ impl From<BloscShuffle> for ShuffleMode {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum BloscCname {
     LZ4,
     LZ4HC,
     BloscLz,
+    #[default]
     ZStd,
     Snappy,
     Zlib,
@@ -50,16 +52,67 @@ impl From<BloscCname> for Compressor {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+fn default_clevel() -> u8 {
+    5
+}
+
+/// Configuration for the blosc codec. Every field is optional in the metadata,
+/// missing fields fall back to the same defaults used by zarr-python. When the
+/// typesize is omitted it is inferred from the array's data type.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct BloscCodecConfig {
-    typesize: usize,
-    cname: BloscCname,
-    clevel: u8,
-    shuffle: BloscShuffle,
-    blocksize: usize,
+    #[serde(default)]
+    pub cname: BloscCname,
+    #[serde(default = "default_clevel")]
+    pub clevel: u8,
+    #[serde(default)]
+    pub shuffle: BloscShuffle,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub typesize: Option<usize>,
+    #[serde(default)]
+    pub blocksize: usize,
+}
+
+impl Default for BloscCodecConfig {
+    fn default() -> Self {
+        Self::new(BloscCname::default(), default_clevel(), BloscShuffle::default())
+    }
 }
 
 impl BloscCodecConfig {
+    /// Create a new config, leaving the typesize to be inferred from the
+    /// array's data type and letting blosc pick the blocksize
+    pub fn new(cname: BloscCname, clevel: u8, shuffle: BloscShuffle) -> Self {
+        Self {
+            cname,
+            clevel,
+            shuffle,
+            typesize: None,
+            blocksize: 0,
+        }
+    }
+
+    /// Check that the config can be used to encode data
+    pub fn validate(&self) -> Result<(), CharizarrError> {
+        self.normalized_clevel()?;
+        if self.typesize == Some(0) {
+            return Err(CharizarrError::CodecError(
+                "Blosc typesize must be a positive integer".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The metadata extension describing this codec, for use when creating arrays
+    pub fn to_extension(&self) -> Result<Extension, CharizarrError> {
+        let configuration =
+            serde_json::to_value(self).map_err(|e| CharizarrError::CodecError(e.to_string()))?;
+        Ok(Extension {
+            name: "blosc".to_string(),
+            configuration,
+        })
+    }
+
     pub fn normalized_blocksize(&self) -> Option<usize> {
         if self.blocksize == 0 {
             None
@@ -68,8 +121,8 @@ impl BloscCodecConfig {
         }
     }
 
-    pub fn normalized_clevel(&self) -> Clevel {
-        match self.clevel {
+    pub fn normalized_clevel(&self) -> Result<Clevel, CharizarrError> {
+        let clevel = match self.clevel {
             0 => Clevel::None,
             1 => Clevel::L1,
             2 => Clevel::L2,
@@ -80,28 +133,49 @@ impl BloscCodecConfig {
             7 => Clevel::L7,
             8 => Clevel::L8,
             9 => Clevel::L9,
-            _ => Clevel::None,
-        }
+            clevel => {
+                return Err(CharizarrError::CodecError(format!(
+                    "Blosc clevel must be between 0 and 9, got {clevel}"
+                )))
+            }
+        };
+        Ok(clevel)
     }
 
-    pub fn normalized_typesize(&self) -> Option<usize> {
+    /// The typesize to shuffle with, falling back to the element size of the
+    /// given data type when it is not set explicitly
+    pub fn normalized_typesize(
+        &self,
+        data_type: &DataType,
+    ) -> Result<Option<usize>, CharizarrError> {
         if self.shuffle == BloscShuffle::NoShuffle {
-            None
-        } else {
-            Some(self.typesize)
+            return Ok(None);
+        }
+
+        match self.typesize.or_else(|| data_type.size()) {
+            Some(typesize) => Ok(Some(typesize)),
+            None => Err(CharizarrError::CodecError(format!(
+                "Blosc typesize is required to shuffle data type {data_type}"
+            ))),
         }
     }
-}
 
-impl From<BloscCodecConfig> for Context {
-    fn from(config: BloscCodecConfig) -> Self {
-        Context::new()
-            .blocksize(config.normalized_blocksize())
-            .compressor(config.cname.clone().into())
-            .unwrap()
-            .clevel(config.normalized_clevel())
-            .shuffle(config.shuffle.clone().into())
-            .typesize(config.normalized_typesize())
+    /// Build a blosc compression context for data of the given type
+    pub fn context(&self, data_type: &DataType) -> Result<Context, CharizarrError> {
+        self.validate()?;
+        let context = Context::new()
+            .blocksize(self.normalized_blocksize())
+            .compressor(self.cname.clone().into())
+            .map_err(|_| {
+                CharizarrError::CodecError(format!(
+                    "Blosc compressor {:?} is not supported by this build of blosc",
+                    self.cname
+                ))
+            })?
+            .clevel(self.normalized_clevel()?)
+            .shuffle(self.shuffle.clone().into())
+            .typesize(self.normalized_typesize(data_type)?);
+        Ok(context)
     }
 }
 
@@ -129,12 +203,11 @@ impl NamedCodec for BloscCodec {
 impl ByteToByteCodec for BloscCodec {
     fn encode(
         &self,
-        _data_type: &DataType,
+        data_type: &DataType,
         config: &Value,
         data: &[u8],
     ) -> Result<Vec<u8>, CharizarrError> {
-        let config = self.parse_config(config)?;
-        let context = Context::from(config);
+        let context = self.parse_config(config)?.context(data_type)?;
 
         let compressed = context.compress(data);
         Ok(compressed.into())
@@ -143,9 +216,13 @@ impl ByteToByteCodec for BloscCodec {
     fn decode(
         &self,
         _data_type: &DataType,
-        _config: &Value,
+        config: &Value,
         data: &[u8],
     ) -> Result<Vec<u8>, CharizarrError> {
+        // The blosc header describes everything needed to decompress, but the
+        // config is still checked so that invalid metadata is surfaced early
+        self.parse_config(config)?.validate()?;
+
         unsafe {
             decompress_bytes(data).map_err(|_| {
                 CharizarrError::CodecError(String::from("Failed to decompress data with Blosc"))
@@ -153,3 +230,70 @@ impl ByteToByteCodec for BloscCodec {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::data_type::CoreDataType;
+
+    use super::*;
+
+    #[test]
+    fn test_blosc_config_defaults() {
+        let config = serde_json::from_value::<BloscCodecConfig>(serde_json::json!({})).unwrap();
+        assert_eq!(config, BloscCodecConfig::default());
+        assert_eq!(config.cname, BloscCname::ZStd);
+        assert_eq!(config.clevel, 5);
+        assert_eq!(config.shuffle, BloscShuffle::Shuffle);
+        assert_eq!(config.typesize, None);
+        assert_eq!(config.normalized_blocksize(), None);
+
+        let extension = config.to_extension().unwrap();
+        assert_eq!(extension.name, "blosc");
+        assert_eq!(
+            extension.configuration,
+            serde_json::json!({"cname": "zstd", "clevel": 5, "shuffle": "shuffle", "blocksize": 0})
+        );
+    }
+
+    #[test]
+    fn test_blosc_config_typesize() {
+        let data_type = DataType::Core(CoreDataType::Float64);
+
+        // Inferred from the data type when omitted
+        let mut config = BloscCodecConfig::new(BloscCname::LZ4, 1, BloscShuffle::BitShuffle);
+        assert_eq!(config.normalized_typesize(&data_type).unwrap(), Some(8));
+
+        // Explicit typesize takes precedence
+        config.typesize = Some(4);
+        assert_eq!(config.normalized_typesize(&data_type).unwrap(), Some(4));
+
+        // Not needed when not shuffling
+        config.shuffle = BloscShuffle::NoShuffle;
+        assert_eq!(config.normalized_typesize(&data_type).unwrap(), None);
+
+        // Unknown element size can't be inferred
+        let data_type = DataType::Extension(Extension {
+            name: "custom".to_string(),
+            configuration: serde_json::json!({}),
+        });
+        let config = BloscCodecConfig::default();
+        assert!(config.normalized_typesize(&data_type).is_err());
+    }
+
+    #[test]
+    fn test_blosc_config_validation() {
+        let config = BloscCodecConfig::new(BloscCname::ZStd, 12, BloscShuffle::Shuffle);
+        assert!(matches!(config.validate(), Err(CharizarrError::CodecError(_))));
+
+        let config = BloscCodecConfig {
+            typesize: Some(0),
+            ..Default::default()
+        };
+        assert!(matches!(config.validate(), Err(CharizarrError::CodecError(_))));
+
+        let codec = BloscCodec::new();
+        let data_type = DataType::Core(CoreDataType::Int16);
+        let config = serde_json::json!({"cname": "zstd", "clevel": 10});
+        assert!(codec.encode(&data_type, &config, &[0, 1, 2, 3]).is_err());
+    }
+}
@@ -0,0 +1,661 @@
+use std::io::Read;
+
+use flate2::read::ZlibDecoder;
+use ruzstd::decoding::StreamingDecoder;
+use serde_json::Value;
+
+use crate::{
+    codec::{ByteToByteCodec, NamedCodec},
+    error::CharizarrError,
+    metadata::DataType,
+};
+
+const BLOSC_HEADER_SIZE: usize = 16;
+const BLOSC_MAX_SPLITS: usize = 16;
+const BLOSC_MIN_BUFFERSIZE: usize = 128;
+const BLOSCLZ_MAX_DISTANCE: usize = 8191;
+
+const BLOSC_DOSHUFFLE: u8 = 0x1;
+const BLOSC_MEMCPYED: u8 = 0x2;
+const BLOSC_DOBITSHUFFLE: u8 = 0x4;
+const BLOSC_DONT_SPLIT: u8 = 0x10;
+
+/// The inner compressor used for a blosc frame, stored in the top three bits
+/// of the header flags
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BloscFormat {
+    BloscLz,
+    LZ4,
+    Snappy,
+    Zlib,
+    ZStd,
+}
+
+impl TryFrom<u8> for BloscFormat {
+    type Error = CharizarrError;
+
+    fn try_from(flags: u8) -> Result<Self, Self::Error> {
+        match flags >> 5 {
+            0 => Ok(BloscFormat::BloscLz),
+            1 => Ok(BloscFormat::LZ4),
+            2 => Ok(BloscFormat::Snappy),
+            3 => Ok(BloscFormat::Zlib),
+            4 => Ok(BloscFormat::ZStd),
+            code => Err(CharizarrError::CodecError(format!(
+                "Unknown blosc compressor format {code}"
+            ))),
+        }
+    }
+}
+
+/// The fixed size header at the start of every blosc1 frame
+#[derive(Debug, Clone)]
+struct BloscHeader {
+    version: u8,
+    flags: u8,
+    typesize: usize,
+    nbytes: usize,
+    blocksize: usize,
+    cbytes: usize,
+}
+
+impl BloscHeader {
+    fn parse(data: &[u8]) -> Result<Self, CharizarrError> {
+        if data.len() < BLOSC_HEADER_SIZE {
+            return Err(CharizarrError::CodecError(
+                "Blosc frame is smaller than the blosc header".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            version: data[0],
+            flags: data[2],
+            typesize: data[3] as usize,
+            nbytes: read_u32(data, 4)? as usize,
+            blocksize: read_u32(data, 8)? as usize,
+            cbytes: read_u32(data, 12)? as usize,
+        })
+    }
+
+    fn format(&self) -> Result<BloscFormat, CharizarrError> {
+        BloscFormat::try_from(self.flags)
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, CharizarrError> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| CharizarrError::CodecError("Blosc frame is truncated".to_string()))
+}
+
+/// Decompress a complete blosc1 frame
+pub fn decompress(data: &[u8]) -> Result<Vec<u8>, CharizarrError> {
+    let header = BloscHeader::parse(data)?;
+    if header.cbytes > data.len() {
+        return Err(CharizarrError::CodecError(format!(
+            "Blosc frame expects {} bytes but only {} are available",
+            header.cbytes,
+            data.len()
+        )));
+    }
+    let data = &data[..header.cbytes];
+
+    if header.nbytes == 0 {
+        return Ok(vec![]);
+    }
+
+    if header.flags & BLOSC_MEMCPYED != 0 {
+        return data
+            .get(BLOSC_HEADER_SIZE..BLOSC_HEADER_SIZE + header.nbytes)
+            .map(|b| b.to_vec())
+            .ok_or_else(|| CharizarrError::CodecError("Blosc frame is truncated".to_string()));
+    }
+
+    if header.blocksize == 0 || header.typesize == 0 {
+        return Err(CharizarrError::CodecError(
+            "Blosc header has an invalid blocksize or typesize".to_string(),
+        ));
+    }
+
+    let format = header.format()?;
+    let nblocks = header.nbytes.div_ceil(header.blocksize);
+    let leftover = header.nbytes % header.blocksize;
+
+    let mut out = vec![0u8; header.nbytes];
+    for (i, block) in out.chunks_mut(header.blocksize).enumerate() {
+        let start = read_u32(data, BLOSC_HEADER_SIZE + i * 4)? as usize;
+        let leftover_block = i == nblocks - 1 && leftover > 0;
+        decompress_block(&header, format, data, start, leftover_block, block)?;
+    }
+
+    Ok(out)
+}
+
+/// Decompress a single block starting at `start` into `dest`, undoing any
+/// shuffle that was applied before compression
+fn decompress_block(
+    header: &BloscHeader,
+    format: BloscFormat,
+    data: &[u8],
+    start: usize,
+    leftover_block: bool,
+    dest: &mut [u8],
+) -> Result<(), CharizarrError> {
+    let bsize = dest.len();
+    let typesize = header.typesize;
+    let doshuffle = header.flags & BLOSC_DOSHUFFLE != 0 && typesize > 1;
+    let dobitshuffle = header.flags & BLOSC_DOBITSHUFFLE != 0 && bsize >= typesize;
+    let dont_split = header.flags & BLOSC_DONT_SPLIT != 0;
+
+    let nsplits = if !dont_split
+        && !leftover_block
+        && typesize <= BLOSC_MAX_SPLITS
+        && bsize / typesize >= BLOSC_MIN_BUFFERSIZE
+    {
+        typesize
+    } else {
+        1
+    };
+    let neblock = bsize / nsplits;
+
+    let mut tmp = vec![0u8; bsize];
+    let mut offset = start;
+    for split in tmp.chunks_mut(neblock) {
+        let cbytes = read_u32(data, offset)? as usize;
+        offset += 4;
+        let src = data
+            .get(offset..offset + cbytes)
+            .ok_or_else(|| CharizarrError::CodecError("Blosc block is truncated".to_string()))?;
+        offset += cbytes;
+
+        if cbytes == neblock {
+            split.copy_from_slice(src);
+        } else {
+            decompress_split(format, src, split)?;
+        }
+    }
+
+    if doshuffle {
+        unshuffle(typesize, &tmp, dest);
+    } else if dobitshuffle {
+        bitunshuffle(typesize, header.version, &tmp, dest);
+    } else {
+        dest.copy_from_slice(&tmp);
+    }
+
+    Ok(())
+}
+
+fn decompress_split(
+    format: BloscFormat,
+    src: &[u8],
+    dest: &mut [u8],
+) -> Result<(), CharizarrError> {
+    let nbytes = match format {
+        BloscFormat::BloscLz => blosclz_decompress(src, dest)?,
+        BloscFormat::LZ4 => lz4_flex::block::decompress_into(src, dest).map_err(|e| {
+            CharizarrError::CodecError(format!("Failed to decompress lz4 block: {e}"))
+        })?,
+        BloscFormat::Zlib => read_into(ZlibDecoder::new(src), dest)?,
+        BloscFormat::ZStd => {
+            let decoder = StreamingDecoder::new(src).map_err(|e| {
+                CharizarrError::CodecError(format!("Failed to decompress zstd block: {e}"))
+            })?;
+            read_into(decoder, dest)?
+        }
+        BloscFormat::Snappy => snap::raw::Decoder::new().decompress(src, dest).map_err(|e| {
+            CharizarrError::CodecError(format!("Failed to decompress snappy block: {e}"))
+        })?,
+    };
+
+    if nbytes != dest.len() {
+        return Err(CharizarrError::CodecError(format!(
+            "Blosc block decompressed to {nbytes} bytes, expected {}",
+            dest.len()
+        )));
+    }
+
+    Ok(())
+}
+
+fn read_into(mut reader: impl Read, dest: &mut [u8]) -> Result<usize, CharizarrError> {
+    let mut out = Vec::with_capacity(dest.len());
+    reader
+        .read_to_end(&mut out)
+        .map_err(|e| CharizarrError::CodecError(format!("Failed to decompress block: {e}")))?;
+    if out.len() != dest.len() {
+        return Ok(out.len());
+    }
+    dest.copy_from_slice(&out);
+    Ok(out.len())
+}
+
+/// Port of blosclz_decompress from c-blosc
+fn blosclz_decompress(src: &[u8], dest: &mut [u8]) -> Result<usize, CharizarrError> {
+    let error = || CharizarrError::CodecError("Corrupt blosclz block".to_string());
+
+    if src.is_empty() {
+        return Ok(0);
+    }
+
+    let mut ip = 0usize;
+    let mut op = 0usize;
+    let mut ctrl = (src[ip] & 31) as usize;
+    ip += 1;
+
+    loop {
+        if ctrl >= 32 {
+            // match
+            let mut len = (ctrl >> 5) - 1;
+            let mut ofs = (ctrl & 31) << 8;
+            if len == 7 - 1 {
+                loop {
+                    let code = *src.get(ip).ok_or_else(error)?;
+                    ip += 1;
+                    len += code as usize;
+                    if code != 255 {
+                        break;
+                    }
+                }
+            }
+            let code = *src.get(ip).ok_or_else(error)? as usize;
+            ip += 1;
+            len += 3;
+
+            // match from 16-bit distance
+            let mut distance = ofs + code + 1;
+            if code == 255 && ofs == (31 << 8) {
+                let hi = *src.get(ip).ok_or_else(error)? as usize;
+                let lo = *src.get(ip + 1).ok_or_else(error)? as usize;
+                ip += 2;
+                ofs = (hi << 8) + lo;
+                distance = ofs + BLOSCLZ_MAX_DISTANCE + 1;
+            }
+
+            if op + len > dest.len() || distance > op {
+                return Err(error());
+            }
+
+            // Copy byte by byte, the reference may overlap the output
+            let reference = op - distance;
+            for i in 0..len {
+                dest[op + i] = dest[reference + i];
+            }
+            op += len;
+        } else {
+            // literal
+            let len = ctrl + 1;
+            if op + len > dest.len() || ip + len > src.len() {
+                return Err(error());
+            }
+            dest[op..op + len].copy_from_slice(&src[ip..ip + len]);
+            op += len;
+            ip += len;
+        }
+
+        if ip >= src.len() {
+            break;
+        }
+        ctrl = src[ip] as usize;
+        ip += 1;
+    }
+
+    Ok(op)
+}
+
+/// Undo the blosc byte shuffle, which groups the nth byte of every element
+/// together. Trailing bytes that don't fill an element are left in place.
+fn unshuffle(typesize: usize, src: &[u8], dest: &mut [u8]) {
+    let nelements = src.len() / typesize;
+    for i in 0..nelements {
+        for j in 0..typesize {
+            dest[i * typesize + j] = src[j * nelements + i];
+        }
+    }
+    let rem = nelements * typesize;
+    dest[rem..].copy_from_slice(&src[rem..]);
+}
+
+/// Undo the blosc bit shuffle, which groups the nth bit of every element
+/// together. Only multiples of eight elements are shuffled.
+fn bitunshuffle(typesize: usize, version: u8, src: &[u8], dest: &mut [u8]) {
+    let nelements = src.len() / typesize;
+    let uneven = nelements % 8;
+    if version <= 2 && uneven > 0 {
+        // Older frames skip the shuffle entirely for uneven blocks
+        dest.copy_from_slice(src);
+        return;
+    }
+    let nelements = nelements - uneven;
+
+    let shuffled = nelements * typesize;
+    dest[..shuffled].fill(0);
+    for j in 0..typesize {
+        for b in 0..8 {
+            let row = (j * 8 + b) * nelements;
+            for i in 0..nelements {
+                let bit = (src[(row + i) / 8] >> ((row + i) % 8)) & 1;
+                dest[i * typesize + j] |= bit << b;
+            }
+        }
+    }
+    dest[shuffled..].copy_from_slice(&src[shuffled..]);
+}
+
+/// A pure rust decoder for blosc1 frames, for use when the blosc system library
+/// is not available. Only decoding is supported, encoding returns an error.
+///
+/// Adapted from https://github.com/Blosc/c-blosc/blob/main/README_CHUNK_FORMAT.rst
+#[derive(Clone, Debug, Default)]
+pub struct BloscDecoderCodec {}
+
+impl BloscDecoderCodec {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl NamedCodec for BloscDecoderCodec {
+    fn resolve_name(&self) -> String {
+        "blosc".to_string()
+    }
+}
+
+impl ByteToByteCodec for BloscDecoderCodec {
+    fn encode(
+        &self,
+        _data_type: &DataType,
+        _config: &Value,
+        _data: &[u8],
+    ) -> Result<Vec<u8>, CharizarrError> {
+        Err(CharizarrError::UnimplementedError(
+            "blosc encoding requires the blosc feature",
+        ))
+    }
+
+    fn decode(
+        &self,
+        _data_type: &DataType,
+        _config: &Value,
+        data: &[u8],
+    ) -> Result<Vec<u8>, CharizarrError> {
+        decompress(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{write::ZlibEncoder, Compression};
+
+    use super::*;
+
+    fn shuffle(typesize: usize, src: &[u8]) -> Vec<u8> {
+        let nelements = src.len() / typesize;
+        let mut dest = src.to_vec();
+        for i in 0..nelements {
+            for j in 0..typesize {
+                dest[j * nelements + i] = src[i * typesize + j];
+            }
+        }
+        dest
+    }
+
+    fn bitshuffle(typesize: usize, src: &[u8]) -> Vec<u8> {
+        let nelements = src.len() / typesize;
+        let mut dest = vec![0u8; src.len()];
+        for j in 0..typesize {
+            for b in 0..8 {
+                let row = (j * 8 + b) * nelements;
+                for i in 0..nelements {
+                    let bit = (src[i * typesize + j] >> b) & 1;
+                    dest[(row + i) / 8] |= bit << ((row + i) % 8);
+                }
+            }
+        }
+        dest
+    }
+
+    /// Assemble a blosc frame from already compressed splits of each block
+    fn frame(
+        flags: u8,
+        typesize: u8,
+        nbytes: usize,
+        blocksize: usize,
+        blocks: Vec<Vec<Vec<u8>>>,
+    ) -> Vec<u8> {
+        let mut body = vec![];
+        let mut starts = vec![];
+        let offset = BLOSC_HEADER_SIZE + blocks.len() * 4;
+        for splits in blocks {
+            starts.push((offset + body.len()) as u32);
+            for split in splits {
+                body.extend((split.len() as u32).to_le_bytes());
+                body.extend(split);
+            }
+        }
+
+        let mut out = vec![2, 1, flags, typesize];
+        out.extend((nbytes as u32).to_le_bytes());
+        out.extend((blocksize as u32).to_le_bytes());
+        out.extend(((offset + body.len()) as u32).to_le_bytes());
+        starts.iter().for_each(|s| out.extend(s.to_le_bytes()));
+        out.extend(body);
+        out
+    }
+
+    fn sample(n: usize) -> Vec<u8> {
+        (0..n as u32)
+            .flat_map(|i| (i % 100 + 1000).to_le_bytes())
+            .collect()
+    }
+
+    #[test]
+    fn test_memcpyed_frame() {
+        let data = [
+            0x02, 0x01, 0x92, 0x02, 0x08, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x18, 0x00,
+            0x00, 0x00, 0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0x04, 0x00,
+        ];
+        let decoded = decompress(&data).unwrap();
+        assert_eq!(decoded, vec![1, 0, 2, 0, 3, 0, 4, 0]);
+
+        // Truncated frames are errors, not panics
+        assert!(decompress(&data[..20]).is_err());
+        assert!(decompress(&data[..8]).is_err());
+    }
+
+    #[test]
+    fn test_lz4_shuffle_split_frame() {
+        let data = sample(256);
+        let shuffled = shuffle(4, &data);
+        let splits = shuffled
+            .chunks(256)
+            .map(lz4_flex::block::compress)
+            .collect::<Vec<_>>();
+        let flags = BLOSC_DOSHUFFLE | (1 << 5);
+        let encoded = frame(flags, 4, data.len(), data.len(), vec![splits]);
+
+        assert_eq!(decompress(&encoded).unwrap(), data);
+    }
+
+    #[test]
+    fn test_zstd_bitshuffle_leftover_frame() {
+        // Two full blocks of 64 elements and a leftover block of 8 elements
+        let data = sample(136);
+        let blocks = data
+            .chunks(256)
+            .map(|block| {
+                let shuffled = bitshuffle(4, block);
+                let compressed = ruzstd::encoding::compress_to_vec(
+                    shuffled.as_slice(),
+                    ruzstd::encoding::CompressionLevel::Fastest,
+                );
+                vec![compressed]
+            })
+            .collect();
+        let flags = BLOSC_DOBITSHUFFLE | BLOSC_DONT_SPLIT | (4 << 5);
+        let encoded = frame(flags, 4, data.len(), 256, blocks);
+
+        assert_eq!(decompress(&encoded).unwrap(), data);
+    }
+
+    #[test]
+    fn test_zlib_frame() {
+        let data = sample(64);
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&data).unwrap();
+        let compressed = encoder.finish().unwrap();
+        let flags = BLOSC_DONT_SPLIT | (3 << 5);
+        let encoded = frame(flags, 4, data.len(), data.len(), vec![vec![compressed]]);
+
+        let codec = BloscDecoderCodec::new();
+        let decoded = codec
+            .decode(
+                &DataType::Core(crate::data_type::CoreDataType::Int32),
+                &Value::Null,
+                &encoded,
+            )
+            .unwrap();
+        assert_eq!(decoded, data);
+    }
+
+    #[test]
+    fn test_snappy_frame() {
+        let data = sample(64);
+        let compressed = snap::raw::Encoder::new().compress_vec(&data).unwrap();
+        let flags = BLOSC_DONT_SPLIT | (2 << 5);
+        let encoded = frame(flags, 4, data.len(), data.len(), vec![vec![compressed]]);
+
+        let codec = BloscDecoderCodec::new();
+        let decoded = codec
+            .decode(
+                &DataType::Core(crate::data_type::CoreDataType::Int32),
+                &Value::Null,
+                &encoded,
+            )
+            .unwrap();
+        assert_eq!(decoded, data);
+    }
+
+    #[test]
+    fn test_blosclz_decompress() {
+        // Literal "abc", a 6 byte match at distance 3, then a run of 4 'c'
+        let src = [0x02, b'a', b'b', b'c', 0x80, 0x02, 0x40, 0x00];
+        let mut dest = vec![0u8; 13];
+        let nbytes = blosclz_decompress(&src, &mut dest).unwrap();
+        assert_eq!(nbytes, 13);
+        assert_eq!(&dest, b"abcabcabccccc");
+
+        // References before the start of the output are rejected
+        let src = [0x00, b'a', 0x40, 0x05];
+        let mut dest = vec![0u8; 8];
+        assert!(blosclz_decompress(&src, &mut dest).is_err());
+    }
+
+    #[cfg(feature = "blosc")]
+    #[test]
+    fn test_matches_blosc_codec() {
+        use crate::{
+            codecs::blosc::{BloscCname, BloscCodec, BloscCodecConfig, BloscShuffle},
+            data_type::CoreDataType,
+        };
+
+        let c_codec = BloscCodec::new();
+        let rs_codec = BloscDecoderCodec::new();
+        let data_type = DataType::Core(CoreDataType::Int32);
+        let data = sample(10_000);
+
+        for cname in [
+            BloscCname::BloscLz,
+            BloscCname::LZ4,
+            BloscCname::LZ4HC,
+            BloscCname::Snappy,
+            BloscCname::ZStd,
+            BloscCname::Zlib,
+        ] {
+            for shuffle in [
+                BloscShuffle::NoShuffle,
+                BloscShuffle::Shuffle,
+                BloscShuffle::BitShuffle,
+            ] {
+                for blocksize in [0, 1000] {
+                    let mut config = BloscCodecConfig::new(cname.clone(), 5, shuffle.clone());
+                    config.blocksize = blocksize;
+                    // Skip compressors the linked blosc library was built without
+                    if config.context(&data_type).is_err() {
+                        continue;
+                    }
+                    let config = serde_json::to_value(&config).unwrap();
+
+                    let encoded = c_codec.encode(&data_type, &config, &data).unwrap();
+                    let expected = c_codec.decode(&data_type, &config, &encoded).unwrap();
+                    let decoded = rs_codec.decode(&data_type, &config, &encoded).unwrap();
+                    assert_eq!(decoded, expected, "{cname:?} {shuffle:?} {blocksize}");
+                    assert_eq!(decoded, data);
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "blosc")]
+    #[test]
+    fn test_matches_blosc_codec_fixtures() {
+        use std::path::Path;
+
+        use crate::{array::ArrayMetadata, codecs::blosc::BloscCodec};
+
+        fn chunk_files(dir: &Path, files: &mut Vec<std::path::PathBuf>) {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    chunk_files(&path, files);
+                } else {
+                    files.push(path);
+                }
+            }
+        }
+
+        let c_codec = BloscCodec::new();
+        let rs_codec = BloscDecoderCodec::new();
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data.zarr");
+
+        let mut checked = 0;
+        for entry in std::fs::read_dir(&root).unwrap() {
+            let dir = entry.unwrap().path();
+            let Ok(raw_metadata) = std::fs::read(dir.join("zarr.json")) else {
+                continue;
+            };
+            let Ok(metadata) = serde_json::from_slice::<ArrayMetadata>(&raw_metadata) else {
+                continue;
+            };
+            let Some(blosc) = metadata.codecs.iter().find(|c| c.name == "blosc") else {
+                continue;
+            };
+
+            let chunk_shape = metadata.chunk_grid.configuration["chunk_shape"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|len| len.as_u64().unwrap() as usize)
+                .product::<usize>();
+            let nbytes = chunk_shape * metadata.data_type.size().unwrap();
+
+            let mut files = vec![];
+            chunk_files(&dir.join("c"), &mut files);
+            for file in files {
+                let encoded = std::fs::read(&file).unwrap();
+                let decoded = rs_codec
+                    .decode(&metadata.data_type, &blosc.configuration, &encoded)
+                    .unwrap();
+                assert_eq!(decoded.len(), nbytes, "{file:?}");
+                // The linked blosc library may lack the compressor of the chunk
+                if let Ok(expected) = c_codec.decode(&metadata.data_type, &blosc.configuration, &encoded) {
+                    assert_eq!(decoded, expected, "{file:?}");
+                }
+                checked += 1;
+            }
+        }
+        assert!(checked > 0);
+    }
+}
@@ -1,3 +1,5 @@
+use half::{bf16, f16};
+use ndarray::Array;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -5,6 +7,8 @@ use crate::{
     zarray::ZArray,
     codec::{ByteToArrayCodec, NamedCodec},
     data_type::CoreDataType,
+    datetime::TimeDataType,
+    structured::StructuredDataType,
     error::CharizarrError,
     metadata::DataType,
 };
@@ -100,6 +104,8 @@ impl ByteToArrayCodec for BytesCodec {
             ZArray::UInt16(arr) => Ok(encode_endian_chunk!(config.endian, arr, u16)),
             ZArray::UInt32(arr) => Ok(encode_endian_chunk!(config.endian, arr, u32)),
             ZArray::UInt64(arr) => Ok(encode_endian_chunk!(config.endian, arr, u64)),
+            ZArray::Float16(arr) => Ok(encode_endian_chunk!(config.endian, arr, f16)),
+            ZArray::BFloat16(arr) => Ok(encode_endian_chunk!(config.endian, arr, bf16)),
             ZArray::Float32(arr) => Ok(encode_endian_chunk!(config.endian, arr, f32)),
             ZArray::Float64(arr) => Ok(encode_endian_chunk!(config.endian, arr, f64)),
             ZArray::Complex64(_) => Err(CharizarrError::UnimplementedError(
@@ -114,7 +120,65 @@ impl ByteToArrayCodec for BytesCodec {
             ZArray::Raw16(_) => Err(CharizarrError::UnimplementedError(
                 "This is ignored for now",
             )),
+            ZArray::DateTime64(arr, _) | ZArray::TimeDelta64(arr, _) => {
+                Ok(encode_endian_chunk!(config.endian, arr, i64))
+            }
+            ZArray::Structured(arr, structured) => Ok(match config.endian {
+                Endian::Little => arr.iter().flatten().copied().collect::<Vec<u8>>(),
+                Endian::Big => arr
+                    .iter()
+                    .flat_map(|record| {
+                        let mut record = record.clone();
+                        structured.swap_fields(&mut record);
+                        record
+                    })
+                    .collect::<Vec<u8>>(),
+            }),
+            ZArray::String(_) | ZArray::Bytes(_) => Err(CharizarrError::CodecError(
+                "Variable length data types must use a vlen codec".to_string(),
+            )),
+        }
+    }
+
+    fn decode_into(
+        &self,
+        data_type: &DataType,
+        config: &Value,
+        data: &[u8],
+        out: &mut [u8],
+    ) -> Result<bool, CharizarrError> {
+        let size = match data_type {
+            DataType::Core(
+                CoreDataType::Bool
+                | CoreDataType::Complex64
+                | CoreDataType::Complex128
+                | CoreDataType::String
+                | CoreDataType::Bytes,
+            )
+            | DataType::Extension(_) => return Ok(false),
+            DataType::Core(core) => match core.size() {
+                Some(size) => size,
+                None => return Ok(false),
+            },
+        };
+        if data.len() != out.len() {
+            return Err(CharizarrError::CodecError(format!(
+                "Expected {} bytes but the chunk has {}",
+                out.len(),
+                data.len()
+            )));
+        }
+
+        let config = self.parse_config(config)?;
+        out.copy_from_slice(data);
+        let native = match config.endian {
+            Endian::Little => cfg!(target_endian = "little"),
+            Endian::Big => cfg!(target_endian = "big"),
+        };
+        if !native {
+            out.chunks_mut(size).for_each(|value| value.reverse());
         }
+        Ok(true)
     }
 
     fn decode(
@@ -124,6 +188,25 @@ impl ByteToArrayCodec for BytesCodec {
         data: &[u8],
     ) -> Result<ZArray, CharizarrError> {
         let config = self.parse_config(config)?;
+        if let Some(time) = TimeDataType::from_data_type(data_type)? {
+            let ticks: ZArray = decode_endian_chunk!(config.endian, data, 8, i64);
+            return Ok(ZArray::from_ticks(time, ticks.try_into()?));
+        }
+        if let Some(structured) = StructuredDataType::from_data_type(data_type)? {
+            let records = data
+                .chunks(structured.itemsize)
+                .map(|record| {
+                    let mut record = record.to_vec();
+                    if let Endian::Big = config.endian {
+                        structured.swap_fields(&mut record);
+                    }
+                    record
+                })
+                .collect::<Vec<Vec<u8>>>();
+            let records = Array::from_vec(records).into_dyn();
+            return Ok(ZArray::Structured(records, structured));
+        }
+
         let DataType::Core(data_type) = data_type else {
             return Err(CharizarrError::CodecError("Invalid data type".to_string()));
         };
@@ -143,6 +226,8 @@ impl ByteToArrayCodec for BytesCodec {
             CoreDataType::UInt16 => Ok(decode_endian_chunk!(config.endian, data, 2, u16)),
             CoreDataType::UInt32 => Ok(decode_endian_chunk!(config.endian, data, 4, u32)),
             CoreDataType::UInt64 => Ok(decode_endian_chunk!(config.endian, data, 8, u64)),
+            CoreDataType::Float16 => Ok(decode_endian_chunk!(config.endian, data, 2, f16)),
+            CoreDataType::BFloat16 => Ok(decode_endian_chunk!(config.endian, data, 2, bf16)),
             CoreDataType::Float32 => Ok(decode_endian_chunk!(config.endian, data, 4, f32)),
             CoreDataType::Float64 => Ok(decode_endian_chunk!(config.endian, data, 8, f64)),
             CoreDataType::Complex64 => Err(CharizarrError::UnimplementedError(
@@ -157,6 +242,9 @@ impl ByteToArrayCodec for BytesCodec {
             CoreDataType::Raw16 => Err(CharizarrError::UnimplementedError(
                 "This is ignored for now",
             )),
+            CoreDataType::String | CoreDataType::Bytes => Err(CharizarrError::CodecError(
+                "Variable length data types must use a vlen codec".to_string(),
+            )),
         }
     }
 }
@@ -189,4 +277,90 @@ mod tests {
 
         assert_eq!(i_array, o_array);
     }
+
+    #[test]
+    fn test_bytes_codec_half() {
+        let codec = BytesCodec::new();
+
+        let config = serde_json::json!({
+            "endian": "big"
+        });
+
+        let data_type = DataType::Core(CoreDataType::Float16);
+        let data = ZArray::Float16(
+            Array::from_vec(vec![f16::from_f32(1.0), f16::from_f32(-2.5)]).into_dyn(),
+        );
+        let encoded = codec.encode(&data_type, &config, &data).unwrap();
+        assert_eq!(encoded, vec![0x3c, 0x00, 0xc1, 0x00]);
+        let decoded = codec.decode(&data_type, &config, &encoded).unwrap();
+        assert_eq!(decoded, data);
+
+        let data_type = DataType::Core(CoreDataType::BFloat16);
+        let data = ZArray::BFloat16(
+            Array::from_vec(vec![bf16::from_f32(1.0), bf16::from_f32(-2.5)]).into_dyn(),
+        );
+        let encoded = codec.encode(&data_type, &config, &data).unwrap();
+        assert_eq!(encoded, vec![0x3f, 0x80, 0xc0, 0x20]);
+        let decoded = codec.decode(&data_type, &config, &encoded).unwrap();
+        assert_eq!(decoded, data);
+    }
+
+    #[test]
+    fn test_bytes_codec_structured() {
+        let codec = BytesCodec::new();
+
+        let config = serde_json::json!({
+            "endian": "big"
+        });
+
+        let structured = StructuredDataType::new(vec![
+            ("lat".to_string(), CoreDataType::Float32),
+            ("qc".to_string(), CoreDataType::UInt8),
+        ])
+        .unwrap();
+        let data_type = structured.to_data_type();
+        let data = ZArray::from_fields(
+            structured,
+            &[
+                ZArray::Float32(Array::from_vec(vec![1.0f32, -2.5]).into_dyn()),
+                ZArray::UInt8(Array::from_vec(vec![0u8, 4]).into_dyn()),
+            ],
+        )
+        .unwrap();
+
+        let encoded = codec.encode(&data_type, &config, &data).unwrap();
+        assert_eq!(encoded, vec![0x3f, 0x80, 0, 0, 0, 0xc0, 0x20, 0, 0, 4]);
+        let decoded = codec.decode(&data_type, &config, &encoded).unwrap();
+        assert_eq!(decoded, data);
+
+        let qc: ArrayD<u8> = decoded.field("qc").unwrap().try_into().unwrap();
+        assert_eq!(qc, Array::from_vec(vec![0u8, 4]).into_dyn());
+        assert!(decoded.field("lon").is_err());
+    }
+
+    #[test]
+    fn test_bytes_codec_decode_into() {
+        let codec = BytesCodec::new();
+        let data_type = DataType::Core(CoreDataType::UInt16);
+        let data = vec![0x01, 0x02, 0x03, 0x04];
+
+        let mut out = [0u8; 4];
+        let config = serde_json::json!({"endian": "big"});
+        assert!(codec.decode_into(&data_type, &config, &data, &mut out).unwrap());
+        assert_eq!(
+            [u16::from_ne_bytes([out[0], out[1]]), u16::from_ne_bytes([out[2], out[3]])],
+            [0x0102, 0x0304]
+        );
+
+        let config = serde_json::json!({"endian": "little"});
+        assert!(codec.decode_into(&data_type, &config, &data, &mut out).unwrap());
+        assert_eq!(
+            [u16::from_ne_bytes([out[0], out[1]]), u16::from_ne_bytes([out[2], out[3]])],
+            [0x0201, 0x0403]
+        );
+
+        assert!(codec.decode_into(&data_type, &config, &data[..2], &mut out).is_err());
+        let bools = DataType::Core(CoreDataType::Bool);
+        assert!(!codec.decode_into(&bools, &config, &data, &mut out).unwrap());
+    }
 }
@@ -1,5 +1,9 @@
 pub mod bytes;
 #[cfg(feature = "blosc")]
 pub mod blosc;
+#[cfg(feature = "blosc-decoder")]
+pub mod blosc_decoder;
 #[cfg(feature = "gzip")]
-pub mod gzip;
\ No newline at end of file
+pub mod gzip;
+pub mod numcodecs;
+pub mod vlen;
@@ -0,0 +1,816 @@
+//! Filters compatible with the numcodecs codecs written by zarr-python, which
+//! store their configuration under `numcodecs.*` codec names.
+//!
+//! Adapted from https://github.com/zarr-developers/numcodecs
+use half::f16;
+use ndarray::ArrayD;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    codec::{ArrayToArrayCodec, ByteToByteCodec, NamedCodec},
+    data_type::CoreDataType,
+    error::CharizarrError,
+    metadata::DataType,
+    zarray::ZArray,
+};
+
+fn parse_config<T: for<'de> Deserialize<'de>>(config: &Value) -> Result<T, CharizarrError> {
+    serde_json::from_value::<T>(config.clone())
+        .map_err(|e| CharizarrError::CodecError(e.to_string()))
+}
+
+/// Resolve an optional numpy data type from a codec config, falling back to
+/// the given data type when it is not set
+fn resolve_dtype(name: &Option<String>, fallback: &DataType) -> Result<DataType, CharizarrError> {
+    match name {
+        Some(name) => Ok(DataType::Core(CoreDataType::parse(name)?)),
+        None => Ok(fallback.clone()),
+    }
+}
+
+/// Round half to even, matching `numpy.around`
+fn round_half_even(v: f64) -> f64 {
+    let rounded = v.round();
+    if (v - v.trunc()).abs() == 0.5 {
+        2.0 * (v / 2.0).round()
+    } else {
+        rounded
+    }
+}
+
+fn round_half_even_f32(v: f32) -> f32 {
+    let rounded = v.round();
+    if (v - v.trunc()).abs() == 0.5 {
+        2.0 * (v / 2.0).round()
+    } else {
+        rounded
+    }
+}
+
+/// Cast values to a data type like numpy's `astype`. Floating point values
+/// cast to an integer type are truncated toward zero and wrap around when out
+/// of range, as numpy does on common platforms, where `as` would saturate.
+fn astype_wrapping(data: &ZArray, dtype: &DataType) -> Result<ZArray, CharizarrError> {
+    if !matches!(data, ZArray::Float16(_) | ZArray::Float32(_) | ZArray::Float64(_)) {
+        return data.astype(dtype);
+    }
+    let values: ArrayD<f64> = data
+        .astype(&DataType::Core(CoreDataType::Float64))?
+        .try_into()?;
+    match dtype {
+        DataType::Core(CoreDataType::Int8) => Ok(ZArray::Int8(values.mapv(|v| v as i128 as i8))),
+        DataType::Core(CoreDataType::Int16) => Ok(ZArray::Int16(values.mapv(|v| v as i128 as i16))),
+        DataType::Core(CoreDataType::Int32) => Ok(ZArray::Int32(values.mapv(|v| v as i128 as i32))),
+        DataType::Core(CoreDataType::Int64) => Ok(ZArray::Int64(values.mapv(|v| v as i128 as i64))),
+        DataType::Core(CoreDataType::UInt8) => Ok(ZArray::UInt8(values.mapv(|v| v as i128 as u8))),
+        DataType::Core(CoreDataType::UInt16) => Ok(ZArray::UInt16(values.mapv(|v| v as i128 as u16))),
+        DataType::Core(CoreDataType::UInt32) => Ok(ZArray::UInt32(values.mapv(|v| v as i128 as u32))),
+        DataType::Core(CoreDataType::UInt64) => Ok(ZArray::UInt64(values.mapv(|v| v as i128 as u64))),
+        _ => data.astype(dtype),
+    }
+}
+
+/// Apply a function over the flattened elements of an array in logical order,
+/// keeping the original shape
+fn map_flat<T: Copy>(arr: &ArrayD<T>, f: impl FnOnce(Vec<T>) -> Vec<T>) -> ArrayD<T> {
+    let values = f(arr.iter().copied().collect());
+    ArrayD::from_shape_vec(arr.raw_dim(), values).expect("Mapping preserves the number of elements")
+}
+
+macro_rules! map_numeric {
+    ($zarray:expr, $arr:ident => $body:expr) => {
+        match $zarray {
+            ZArray::Int8($arr) => Ok(ZArray::Int8($body)),
+            ZArray::Int16($arr) => Ok(ZArray::Int16($body)),
+            ZArray::Int32($arr) => Ok(ZArray::Int32($body)),
+            ZArray::Int64($arr) => Ok(ZArray::Int64($body)),
+            ZArray::UInt8($arr) => Ok(ZArray::UInt8($body)),
+            ZArray::UInt16($arr) => Ok(ZArray::UInt16($body)),
+            ZArray::UInt32($arr) => Ok(ZArray::UInt32($body)),
+            ZArray::UInt64($arr) => Ok(ZArray::UInt64($body)),
+            ZArray::Float16($arr) => Ok(ZArray::Float16($body)),
+            ZArray::Float32($arr) => Ok(ZArray::Float32($body)),
+            ZArray::Float64($arr) => Ok(ZArray::Float64($body)),
+            other => Err(CharizarrError::TypeError(other.data_type().to_string())),
+        }
+    };
+}
+
+/// Element arithmetic used by the delta filter, integers wrap on overflow the
+/// same way numpy does
+trait DeltaElement: Copy {
+    fn delta(self, prev: Self) -> Self;
+    fn accumulate(self, prev: Self) -> Self;
+}
+
+macro_rules! delta_int {
+    ($d_type:ty) => {
+        impl DeltaElement for $d_type {
+            fn delta(self, prev: Self) -> Self {
+                self.wrapping_sub(prev)
+            }
+
+            fn accumulate(self, prev: Self) -> Self {
+                self.wrapping_add(prev)
+            }
+        }
+    };
+}
+
+macro_rules! delta_float {
+    ($d_type:ty) => {
+        impl DeltaElement for $d_type {
+            fn delta(self, prev: Self) -> Self {
+                self - prev
+            }
+
+            fn accumulate(self, prev: Self) -> Self {
+                self + prev
+            }
+        }
+    };
+}
+
+delta_int!(i8);
+delta_int!(i16);
+delta_int!(i32);
+delta_int!(i64);
+delta_int!(u8);
+delta_int!(u16);
+delta_int!(u32);
+delta_int!(u64);
+delta_float!(f16);
+delta_float!(f32);
+delta_float!(f64);
+
+fn delta_encode<T: DeltaElement>(arr: &ArrayD<T>) -> ArrayD<T> {
+    map_flat(arr, |values| {
+        let mut out = Vec::with_capacity(values.len());
+        let mut prev = None;
+        for v in values {
+            out.push(prev.map_or(v, |p| v.delta(p)));
+            prev = Some(v);
+        }
+        out
+    })
+}
+
+fn delta_decode<T: DeltaElement>(arr: &ArrayD<T>) -> ArrayD<T> {
+    map_flat(arr, |values| {
+        let mut out = Vec::with_capacity(values.len());
+        let mut acc: Option<T> = None;
+        for v in values {
+            let next = acc.map_or(v, |a| v.accumulate(a));
+            out.push(next);
+            acc = Some(next);
+        }
+        out
+    })
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeltaCodecConfig {
+    pub dtype: Option<String>,
+    pub astype: Option<String>,
+}
+
+/// Stores the differences between consecutive elements
+///
+/// Adapted from https://numcodecs.readthedocs.io/en/stable/filter/delta.html
+#[derive(Clone, Debug, Default)]
+pub struct DeltaCodec {}
+
+impl DeltaCodec {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl NamedCodec for DeltaCodec {
+    fn resolve_name(&self) -> String {
+        "numcodecs.delta".to_string()
+    }
+}
+
+impl ArrayToArrayCodec for DeltaCodec {
+    fn resolve_data_type(
+        &self,
+        data_type: &DataType,
+        config: &Value,
+    ) -> Result<DataType, CharizarrError> {
+        let config: DeltaCodecConfig = parse_config(config)?;
+        let dtype = resolve_dtype(&config.dtype, data_type)?;
+        resolve_dtype(&config.astype, &dtype)
+    }
+
+    fn encode(
+        &self,
+        data_type: &DataType,
+        config: &Value,
+        data: &ZArray,
+    ) -> Result<ZArray, CharizarrError> {
+        let astype = self.resolve_data_type(data_type, config)?;
+        let encoded: ZArray = map_numeric!(data, arr => delta_encode(arr))?;
+        encoded.astype(&astype)
+    }
+
+    fn decode(
+        &self,
+        data_type: &DataType,
+        _config: &Value,
+        data: &ZArray,
+    ) -> Result<ZArray, CharizarrError> {
+        let data = data.astype(data_type)?;
+        map_numeric!(&data, arr => delta_decode(arr))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixedScaleOffsetCodecConfig {
+    pub offset: f64,
+    pub scale: f64,
+    pub dtype: Option<String>,
+    pub astype: Option<String>,
+}
+
+/// Stores `round((x - offset) * scale)`, typically as a smaller integer type
+///
+/// Adapted from https://numcodecs.readthedocs.io/en/stable/filter/fixedscaleoffset.html
+#[derive(Clone, Debug, Default)]
+pub struct FixedScaleOffsetCodec {}
+
+impl FixedScaleOffsetCodec {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl NamedCodec for FixedScaleOffsetCodec {
+    fn resolve_name(&self) -> String {
+        "numcodecs.fixedscaleoffset".to_string()
+    }
+}
+
+impl ArrayToArrayCodec for FixedScaleOffsetCodec {
+    fn resolve_data_type(
+        &self,
+        data_type: &DataType,
+        config: &Value,
+    ) -> Result<DataType, CharizarrError> {
+        let config: FixedScaleOffsetCodecConfig = parse_config(config)?;
+        let dtype = resolve_dtype(&config.dtype, data_type)?;
+        resolve_dtype(&config.astype, &dtype)
+    }
+
+    fn encode(
+        &self,
+        data_type: &DataType,
+        config: &Value,
+        data: &ZArray,
+    ) -> Result<ZArray, CharizarrError> {
+        let astype = self.resolve_data_type(data_type, config)?;
+        let config: FixedScaleOffsetCodecConfig = parse_config(config)?;
+
+        // numpy keeps float16 and float32 arithmetic in their own precision,
+        // everything else is promoted to double precision
+        let encoded = match data {
+            ZArray::Float16(arr) => {
+                let (offset, scale) = (f16::from_f64(config.offset), f16::from_f64(config.scale));
+                ZArray::Float16(arr.mapv(|v| {
+                    f16::from_f32(round_half_even_f32(((v - offset) * scale).to_f32()))
+                }))
+            }
+            ZArray::Float32(arr) => {
+                let (offset, scale) = (config.offset as f32, config.scale as f32);
+                ZArray::Float32(arr.mapv(|v| round_half_even_f32((v - offset) * scale)))
+            }
+            _ => {
+                let arr: ArrayD<f64> = data
+                    .astype(&DataType::Core(CoreDataType::Float64))?
+                    .try_into()?;
+                ZArray::Float64(arr.mapv(|v| round_half_even((v - config.offset) * config.scale)))
+            }
+        };
+        astype_wrapping(&encoded, &astype)
+    }
+
+    fn decode(
+        &self,
+        data_type: &DataType,
+        config: &Value,
+        data: &ZArray,
+    ) -> Result<ZArray, CharizarrError> {
+        let config: FixedScaleOffsetCodecConfig = parse_config(config)?;
+
+        // Floating point encoded values keep their precision, integers are
+        // promoted to double precision
+        let decoded = match data {
+            ZArray::Float16(arr) => {
+                let (offset, scale) = (f16::from_f64(config.offset), f16::from_f64(config.scale));
+                ZArray::Float16(arr.mapv(|v| v / scale + offset))
+            }
+            ZArray::Float32(arr) => {
+                let (offset, scale) = (config.offset as f32, config.scale as f32);
+                ZArray::Float32(arr.mapv(|v| v / scale + offset))
+            }
+            _ => {
+                let arr: ArrayD<f64> = data
+                    .astype(&DataType::Core(CoreDataType::Float64))?
+                    .try_into()?;
+                ZArray::Float64(arr.mapv(|v| v / config.scale + config.offset))
+            }
+        };
+        astype_wrapping(&decoded, data_type)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuantizeCodecConfig {
+    pub digits: i32,
+    pub dtype: Option<String>,
+    pub astype: Option<String>,
+}
+
+impl QuantizeCodecConfig {
+    /// The power of two the values are quantized to, for the configured
+    /// number of decimal digits
+    fn scale(&self) -> f64 {
+        let precision = 10f64.powi(-self.digits);
+        let exp = precision.log10();
+        let exp = if exp < 0.0 { exp.floor() } else { exp.ceil() };
+        let bits = 10f64.powf(-exp).log2().ceil();
+        2f64.powf(bits)
+    }
+}
+
+/// Quantizes floating point values to a given number of decimal digits
+///
+/// Adapted from https://numcodecs.readthedocs.io/en/stable/filter/quantize.html
+#[derive(Clone, Debug, Default)]
+pub struct QuantizeCodec {}
+
+impl QuantizeCodec {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl NamedCodec for QuantizeCodec {
+    fn resolve_name(&self) -> String {
+        "numcodecs.quantize".to_string()
+    }
+}
+
+impl ArrayToArrayCodec for QuantizeCodec {
+    fn resolve_data_type(
+        &self,
+        data_type: &DataType,
+        config: &Value,
+    ) -> Result<DataType, CharizarrError> {
+        let config: QuantizeCodecConfig = parse_config(config)?;
+        let dtype = resolve_dtype(&config.dtype, data_type)?;
+        resolve_dtype(&config.astype, &dtype)
+    }
+
+    fn encode(
+        &self,
+        data_type: &DataType,
+        config: &Value,
+        data: &ZArray,
+    ) -> Result<ZArray, CharizarrError> {
+        let astype = self.resolve_data_type(data_type, config)?;
+        let config: QuantizeCodecConfig = parse_config(config)?;
+        let scale = config.scale();
+
+        let encoded = match data {
+            ZArray::Float16(arr) => {
+                let scale = f16::from_f64(scale);
+                ZArray::Float16(arr.mapv(|v| {
+                    f16::from_f32(round_half_even_f32((scale * v).to_f32())) / scale
+                }))
+            }
+            ZArray::Float32(arr) => {
+                let scale = scale as f32;
+                ZArray::Float32(arr.mapv(|v| round_half_even_f32(scale * v) / scale))
+            }
+            ZArray::Float64(arr) => {
+                ZArray::Float64(arr.mapv(|v| round_half_even(scale * v) / scale))
+            }
+            other => return Err(CharizarrError::TypeError(other.data_type().to_string())),
+        };
+        astype_wrapping(&encoded, &astype)
+    }
+
+    fn decode(
+        &self,
+        data_type: &DataType,
+        _config: &Value,
+        data: &ZArray,
+    ) -> Result<ZArray, CharizarrError> {
+        astype_wrapping(data, data_type)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitRoundCodecConfig {
+    pub keepbits: u32,
+}
+
+macro_rules! bitround {
+    ($arr:expr, $keepbits:expr, $u_type:ty, $maxbits:expr, $from_bits:path) => {{
+        if $keepbits > $maxbits {
+            return Err(CharizarrError::CodecError(format!(
+                "keepbits must be at most {} for this data type",
+                $maxbits
+            )));
+        }
+        if $keepbits == $maxbits {
+            $arr.clone()
+        } else {
+            let maskbits = $maxbits - $keepbits;
+            let mask = (<$u_type>::MAX >> maskbits) << maskbits;
+            let half_quantum = (1 << (maskbits - 1)) - 1;
+            $arr.mapv(|v| {
+                let b = v.to_bits();
+                let b = b.wrapping_add(((b >> maskbits) & 1) + half_quantum);
+                $from_bits(b & mask)
+            })
+        }
+    }};
+}
+
+/// Rounds the mantissa of floating point values to a given number of bits,
+/// so the values compress better
+///
+/// Adapted from https://numcodecs.readthedocs.io/en/stable/filter/bitround.html
+#[derive(Clone, Debug, Default)]
+pub struct BitRoundCodec {}
+
+impl BitRoundCodec {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl NamedCodec for BitRoundCodec {
+    fn resolve_name(&self) -> String {
+        "numcodecs.bitround".to_string()
+    }
+}
+
+impl ArrayToArrayCodec for BitRoundCodec {
+    fn encode(
+        &self,
+        _data_type: &DataType,
+        config: &Value,
+        data: &ZArray,
+    ) -> Result<ZArray, CharizarrError> {
+        let config: BitRoundCodecConfig = parse_config(config)?;
+        match data {
+            ZArray::Float16(arr) => Ok(ZArray::Float16(bitround!(
+                arr,
+                config.keepbits,
+                u16,
+                10,
+                f16::from_bits
+            ))),
+            ZArray::Float32(arr) => Ok(ZArray::Float32(bitround!(
+                arr,
+                config.keepbits,
+                u32,
+                23,
+                f32::from_bits
+            ))),
+            ZArray::Float64(arr) => Ok(ZArray::Float64(bitround!(
+                arr,
+                config.keepbits,
+                u64,
+                52,
+                f64::from_bits
+            ))),
+            other => Err(CharizarrError::TypeError(other.data_type().to_string())),
+        }
+    }
+
+    fn decode(
+        &self,
+        _data_type: &DataType,
+        _config: &Value,
+        data: &ZArray,
+    ) -> Result<ZArray, CharizarrError> {
+        Ok(data.clone())
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ShuffleCodecConfig {
+    pub elementsize: Option<usize>,
+}
+
+/// Groups the nth byte of every element together
+///
+/// Adapted from https://numcodecs.readthedocs.io/en/stable/filter/shuffle.html
+#[derive(Clone, Debug, Default)]
+pub struct ShuffleCodec {}
+
+impl ShuffleCodec {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// The element size from the config, inferred from the data type when
+    /// it is not set
+    fn elementsize(&self, data_type: &DataType, config: &Value) -> Result<usize, CharizarrError> {
+        let config: ShuffleCodecConfig = parse_config(config)?;
+        config
+            .elementsize
+            .or_else(|| data_type.size())
+            .ok_or_else(|| {
+                CharizarrError::CodecError(format!(
+                    "Shuffle elementsize is required for data type {data_type}"
+                ))
+            })
+    }
+}
+
+impl NamedCodec for ShuffleCodec {
+    fn resolve_name(&self) -> String {
+        "numcodecs.shuffle".to_string()
+    }
+}
+
+impl ByteToByteCodec for ShuffleCodec {
+    fn encode(
+        &self,
+        data_type: &DataType,
+        config: &Value,
+        data: &[u8],
+    ) -> Result<Vec<u8>, CharizarrError> {
+        let elementsize = self.elementsize(data_type, config)?;
+        let mut out = data.to_vec();
+        if elementsize <= 1 {
+            return Ok(out);
+        }
+
+        let count = data.len() / elementsize;
+        for i in 0..count {
+            for j in 0..elementsize {
+                out[j * count + i] = data[i * elementsize + j];
+            }
+        }
+        Ok(out)
+    }
+
+    fn decode(
+        &self,
+        data_type: &DataType,
+        config: &Value,
+        data: &[u8],
+    ) -> Result<Vec<u8>, CharizarrError> {
+        let elementsize = self.elementsize(data_type, config)?;
+        let mut out = data.to_vec();
+        if elementsize <= 1 {
+            return Ok(out);
+        }
+
+        let count = data.len() / elementsize;
+        for i in 0..count {
+            for j in 0..elementsize {
+                out[i * elementsize + j] = data[j * count + i];
+            }
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{Array, IxDyn};
+
+    use crate::{
+        chunk::{decode_chunk, encode_chunk},
+        codec_registry::CodecRegistry,
+        data_type_registry::DataTypeRegistry,
+        metadata::Extension,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_delta() {
+        let codec = DeltaCodec::new();
+        let data_type = DataType::Core(CoreDataType::Int32);
+        let config = serde_json::json!({"dtype": "<i4", "astype": "<i2"});
+        let data = ZArray::Int32(Array::from_vec(vec![100, 101, 103, 106, 100]).into_dyn());
+
+        assert_eq!(
+            codec.resolve_data_type(&data_type, &config).unwrap(),
+            DataType::Core(CoreDataType::Int16)
+        );
+
+        let encoded = codec.encode(&data_type, &config, &data).unwrap();
+        assert_eq!(
+            encoded,
+            ZArray::Int16(Array::from_vec(vec![100, 1, 2, 3, -6]).into_dyn())
+        );
+        let decoded = codec.decode(&data_type, &config, &encoded).unwrap();
+        assert_eq!(decoded, data);
+
+        // Integers wrap on overflow like numpy
+        let data_type = DataType::Core(CoreDataType::UInt8);
+        let data = ZArray::UInt8(Array::from_vec(vec![250, 5, 255]).into_dyn());
+        let config = serde_json::json!({"dtype": "|u1"});
+        let encoded = codec.encode(&data_type, &config, &data).unwrap();
+        assert_eq!(
+            encoded,
+            ZArray::UInt8(Array::from_vec(vec![250, 11, 250]).into_dyn())
+        );
+        assert_eq!(codec.decode(&data_type, &config, &encoded).unwrap(), data);
+    }
+
+    #[test]
+    fn test_fixed_scale_offset() {
+        // Example from the numcodecs documentation
+        let codec = FixedScaleOffsetCodec::new();
+        let data_type = DataType::Core(CoreDataType::Float64);
+        let config =
+            serde_json::json!({"offset": 1000, "scale": 10, "dtype": "<f8", "astype": "|u1"});
+        let values = (0..10).map(|i| 1000.0 + i as f64 / 9.0).collect::<Vec<_>>();
+        let data = ZArray::Float64(Array::from_vec(values).into_dyn());
+
+        let encoded = codec.encode(&data_type, &config, &data).unwrap();
+        assert_eq!(
+            encoded,
+            ZArray::UInt8(Array::from_vec(vec![0, 1, 2, 3, 4, 6, 7, 8, 9, 10]).into_dyn())
+        );
+
+        let decoded: ArrayD<f64> = codec
+            .decode(&data_type, &config, &encoded)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let expected = [
+            1000.0, 1000.1, 1000.2, 1000.3, 1000.4, 1000.6, 1000.7, 1000.8, 1000.9, 1001.0,
+        ];
+        decoded
+            .iter()
+            .zip(expected)
+            .for_each(|(a, b)| assert!((a - b).abs() < 1e-9));
+
+        // The finer scales from the same documentation
+        let config =
+            serde_json::json!({"offset": 1000, "scale": 100, "dtype": "<f8", "astype": "|u1"});
+        assert_eq!(
+            codec.encode(&data_type, &config, &data).unwrap(),
+            ZArray::UInt8(Array::from_vec(vec![0, 11, 22, 33, 44, 56, 67, 78, 89, 100]).into_dyn())
+        );
+        let config =
+            serde_json::json!({"offset": 1000, "scale": 1000, "dtype": "<f8", "astype": "<u2"});
+        assert_eq!(
+            codec.encode(&data_type, &config, &data).unwrap(),
+            ZArray::UInt16(
+                Array::from_vec(vec![0, 111, 222, 333, 444, 556, 667, 778, 889, 1000]).into_dyn()
+            )
+        );
+    }
+
+    #[test]
+    fn test_fixed_scale_offset_numpy_casts() {
+        let codec = FixedScaleOffsetCodec::new();
+
+        // A float32 encoding is decoded in single precision, as numpy does
+        let data_type = DataType::Core(CoreDataType::Float64);
+        let config = serde_json::json!({"offset": 0, "scale": 3, "dtype": "<f8", "astype": "<f4"});
+        let encoded = ZArray::Float32(Array::from_vec(vec![1.0f32]).into_dyn());
+        assert_eq!(
+            codec.decode(&data_type, &config, &encoded).unwrap(),
+            ZArray::Float64(Array::from_vec(vec![(1.0f32 / 3.0f32) as f64]).into_dyn())
+        );
+
+        // Values out of range of the integer type wrap around like numpy's
+        // astype, so np.array([300.0, -1.0]).astype("u1") is [44, 255]
+        let config = serde_json::json!({"offset": 0, "scale": 1, "dtype": "<f8", "astype": "|u1"});
+        let data = ZArray::Float64(Array::from_vec(vec![300.0, -1.0]).into_dyn());
+        assert_eq!(
+            codec.encode(&data_type, &config, &data).unwrap(),
+            ZArray::UInt8(Array::from_vec(vec![44, 255]).into_dyn())
+        );
+
+        let data_type = DataType::Core(CoreDataType::Int8);
+        let config = serde_json::json!({"offset": 0, "scale": 1, "dtype": "|i1", "astype": "|u1"});
+        let encoded = ZArray::UInt8(Array::from_vec(vec![200, 5]).into_dyn());
+        assert_eq!(
+            codec.decode(&data_type, &config, &encoded).unwrap(),
+            ZArray::Int8(Array::from_vec(vec![-56, 5]).into_dyn())
+        );
+    }
+
+    #[test]
+    fn test_quantize() {
+        // Example from the numcodecs documentation
+        let codec = QuantizeCodec::new();
+        let data_type = DataType::Core(CoreDataType::Float64);
+        let config = serde_json::json!({"digits": 1, "dtype": "<f8"});
+        let values = (0..10).map(|i| i as f64 / 9.0).collect::<Vec<_>>();
+        let data = ZArray::Float64(Array::from_vec(values).into_dyn());
+
+        let encoded = codec.encode(&data_type, &config, &data).unwrap();
+        let expected = vec![
+            0.0, 0.125, 0.25, 0.3125, 0.4375, 0.5625, 0.6875, 0.75, 0.875, 1.0,
+        ];
+        assert_eq!(
+            encoded,
+            ZArray::Float64(Array::from_vec(expected).into_dyn())
+        );
+        assert_eq!(
+            codec.decode(&data_type, &config, &encoded).unwrap(),
+            encoded
+        );
+
+        let config = serde_json::json!({"digits": 2, "dtype": "<f8"});
+        let expected = vec![
+            0.0, 0.109375, 0.21875, 0.3359375, 0.4453125, 0.5546875, 0.6640625, 0.78125, 0.890625,
+            1.0,
+        ];
+        assert_eq!(
+            codec.encode(&data_type, &config, &data).unwrap(),
+            ZArray::Float64(Array::from_vec(expected).into_dyn())
+        );
+
+        let data = ZArray::Int32(Array::from_vec(vec![1, 2]).into_dyn());
+        assert!(codec.encode(&data_type, &config, &data).is_err());
+    }
+
+    #[test]
+    fn test_bitround() {
+        let codec = BitRoundCodec::new();
+        let data_type = DataType::Core(CoreDataType::Float32);
+        let config = serde_json::json!({"keepbits": 2});
+        let data = ZArray::Float32(Array::from_vec(vec![1.1f32, 1.2, -3.0]).into_dyn());
+
+        let encoded = codec.encode(&data_type, &config, &data).unwrap();
+        assert_eq!(
+            encoded,
+            ZArray::Float32(Array::from_vec(vec![1.0f32, 1.25, -3.0]).into_dyn())
+        );
+
+        let config = serde_json::json!({"keepbits": 23});
+        assert_eq!(codec.encode(&data_type, &config, &data).unwrap(), data);
+
+        let config = serde_json::json!({"keepbits": 24});
+        assert!(codec.encode(&data_type, &config, &data).is_err());
+    }
+
+    #[test]
+    fn test_shuffle() {
+        let codec = ShuffleCodec::new();
+        let data_type = DataType::Core(CoreDataType::Int32);
+        let data = vec![1u8, 2, 3, 4, 5, 6, 7, 8];
+
+        let config = serde_json::json!({"elementsize": 4});
+        let encoded = codec.encode(&data_type, &config, &data).unwrap();
+        assert_eq!(encoded, vec![1, 5, 2, 6, 3, 7, 4, 8]);
+        assert_eq!(codec.decode(&data_type, &config, &encoded).unwrap(), data);
+
+        // Element size is inferred from the data type when omitted
+        let data_type = DataType::Core(CoreDataType::Int16);
+        let encoded = codec
+            .encode(&data_type, &serde_json::json!({}), &data)
+            .unwrap();
+        assert_eq!(encoded, vec![1, 3, 5, 7, 2, 4, 6, 8]);
+    }
+
+    #[test]
+    fn test_numcodecs_chain() {
+        // The numcodecs filters are registered by default
+        let registry = CodecRegistry::default();
+        let codecs = vec![
+            Extension {
+                name: "numcodecs.delta".to_string(),
+                configuration: serde_json::json!({"dtype": "<i8", "astype": "<i2"}),
+            },
+            Extension {
+                name: "bytes".to_string(),
+                configuration: serde_json::json!({"endian": "little"}),
+            },
+            Extension {
+                name: "numcodecs.shuffle".to_string(),
+                configuration: serde_json::json!({}),
+            },
+        ];
+        let data_type = DataType::Core(CoreDataType::Int64);
+        let data = ZArray::Int64(
+            Array::from_vec(vec![10i64, 12, 15, 19])
+                .into_shape(IxDyn(&[2, 2]))
+                .unwrap(),
+        );
+
+        // Delta encoded as int16, then shuffled with the int16 element size
+        let bytes = encode_chunk(&registry, &DataTypeRegistry::default(), &codecs, &data_type, &data).unwrap();
+        assert_eq!(bytes, vec![10, 2, 3, 4, 0, 0, 0, 0]);
+
+        let decoded = decode_chunk(&registry, &DataTypeRegistry::default(), &codecs, &data_type, bytes).unwrap();
+        assert_eq!(decoded.reshape(&[2, 2]), data);
+    }
+}
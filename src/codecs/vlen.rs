@@ -0,0 +1,181 @@
+use serde_json::Value;
+
+use crate::{
+    codec::{ByteToArrayCodec, NamedCodec},
+    error::CharizarrError,
+    metadata::DataType,
+    zarray::ZArray,
+};
+
+/// Encode items as a little endian u32 item count, followed by each item
+/// prefixed with its little endian u32 length
+fn encode_items<'a>(items: impl ExactSizeIterator<Item = &'a [u8]>) -> Vec<u8> {
+    let mut out = Vec::with_capacity(4 + items.len() * 4);
+    out.extend((items.len() as u32).to_le_bytes());
+    for item in items {
+        out.extend((item.len() as u32).to_le_bytes());
+        out.extend(item);
+    }
+    out
+}
+
+fn decode_items(data: &[u8]) -> Result<Vec<&[u8]>, CharizarrError> {
+    let read_u32 = |offset: usize| {
+        data.get(offset..offset + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+            .ok_or_else(|| {
+                CharizarrError::CodecError("Variable length chunk is truncated".to_string())
+            })
+    };
+
+    let nitems = read_u32(0)?;
+    let mut offset = 4;
+    let mut items = Vec::with_capacity(nitems);
+    for _ in 0..nitems {
+        let len = read_u32(offset)?;
+        offset += 4;
+        let item = data.get(offset..offset + len).ok_or_else(|| {
+            CharizarrError::CodecError("Variable length chunk is truncated".to_string())
+        })?;
+        offset += len;
+        items.push(item);
+    }
+
+    Ok(items)
+}
+
+/// Encodes variable length utf8 strings, compatible with zarr-python and numcodecs
+///
+/// Adapted from https://numcodecs.readthedocs.io/en/stable/other/vlen.html
+#[derive(Clone, Debug, Default)]
+pub struct VLenUtf8Codec {}
+
+impl VLenUtf8Codec {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl NamedCodec for VLenUtf8Codec {
+    fn resolve_name(&self) -> String {
+        "vlen-utf8".to_string()
+    }
+}
+
+impl ByteToArrayCodec for VLenUtf8Codec {
+    fn encode(
+        &self,
+        _data_type: &DataType,
+        _config: &Value,
+        data: &ZArray,
+    ) -> Result<Vec<u8>, CharizarrError> {
+        let ZArray::String(arr) = data else {
+            return Err(CharizarrError::TypeError("string".to_string()));
+        };
+        Ok(encode_items(arr.iter().map(|s| s.as_bytes())))
+    }
+
+    fn decode(
+        &self,
+        _data_type: &DataType,
+        _config: &Value,
+        data: &[u8],
+    ) -> Result<ZArray, CharizarrError> {
+        let items = decode_items(data)?
+            .into_iter()
+            .map(|item| {
+                String::from_utf8(item.to_vec())
+                    .map_err(|e| CharizarrError::CodecError(format!("Invalid utf8 string: {e}")))
+            })
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(items.into())
+    }
+}
+
+/// Encodes variable length byte strings, compatible with zarr-python and numcodecs
+///
+/// Adapted from https://numcodecs.readthedocs.io/en/stable/other/vlen.html
+#[derive(Clone, Debug, Default)]
+pub struct VLenBytesCodec {}
+
+impl VLenBytesCodec {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl NamedCodec for VLenBytesCodec {
+    fn resolve_name(&self) -> String {
+        "vlen-bytes".to_string()
+    }
+}
+
+impl ByteToArrayCodec for VLenBytesCodec {
+    fn encode(
+        &self,
+        _data_type: &DataType,
+        _config: &Value,
+        data: &ZArray,
+    ) -> Result<Vec<u8>, CharizarrError> {
+        let ZArray::Bytes(arr) = data else {
+            return Err(CharizarrError::TypeError("bytes".to_string()));
+        };
+        Ok(encode_items(arr.iter().map(|b| b.as_slice())))
+    }
+
+    fn decode(
+        &self,
+        _data_type: &DataType,
+        _config: &Value,
+        data: &[u8],
+    ) -> Result<ZArray, CharizarrError> {
+        let items = decode_items(data)?
+            .into_iter()
+            .map(|item| item.to_vec())
+            .collect::<Vec<Vec<u8>>>();
+        Ok(items.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::prelude::*;
+
+    use crate::data_type::CoreDataType;
+
+    use super::*;
+
+    #[test]
+    fn test_vlen_utf8_codec() {
+        let codec = VLenUtf8Codec::new();
+        let data_type = DataType::Core(CoreDataType::String);
+        let config = serde_json::json!({});
+        let data = ZArray::String(
+            Array::from_vec(vec!["KBOS".to_string(), "".to_string(), "ß".to_string()]).into_dyn(),
+        );
+
+        let encoded = codec.encode(&data_type, &config, &data).unwrap();
+        assert_eq!(
+            encoded,
+            vec![
+                3, 0, 0, 0, 4, 0, 0, 0, b'K', b'B', b'O', b'S', 0, 0, 0, 0, 2, 0, 0, 0, 0xc3, 0x9f
+            ]
+        );
+        let decoded = codec.decode(&data_type, &config, &encoded).unwrap();
+        assert_eq!(decoded, data);
+
+        assert!(codec.decode(&data_type, &config, &encoded[..10]).is_err());
+    }
+
+    #[test]
+    fn test_vlen_bytes_codec() {
+        let codec = VLenBytesCodec::new();
+        let data_type = DataType::Core(CoreDataType::Bytes);
+        let config = serde_json::json!({});
+        let data = ZArray::Bytes(Array::from_vec(vec![vec![0u8, 255], vec![]]).into_dyn());
+
+        let encoded = codec.encode(&data_type, &config, &data).unwrap();
+        let decoded = codec.decode(&data_type, &config, &encoded).unwrap();
+        assert_eq!(decoded, data);
+    }
+}
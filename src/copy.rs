@@ -0,0 +1,130 @@
+use futures::{stream, StreamExt, TryStreamExt};
+
+use crate::{
+    array::DEFAULT_CONCURRENCY,
+    error::CharizarrError,
+    metadata::Extension,
+    store::{ListableStore, ReadableStore, WriteableStore},
+};
+
+/// Options for copying arrays and groups to another store
+#[derive(Debug, Clone)]
+pub struct CopyOptions {
+    pub(crate) concurrency: usize,
+    pub(crate) resume: bool,
+    pub(crate) codecs: Option<Vec<Extension>>,
+}
+
+impl Default for CopyOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: DEFAULT_CONCURRENCY,
+            resume: false,
+            codecs: None,
+        }
+    }
+}
+
+impl CopyOptions {
+    /// Limit the number of keys that are copied at the same time. The limit
+    /// is at least one.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Skip chunks already in the destination with the same size as the
+    /// source, so an interrupted copy can be picked up where it stopped.
+    /// Metadata is always copied again. When the codecs change, each chunk is
+    /// still read and encoded, and only its write is skipped when the encoded
+    /// size matches the destination.
+    pub fn with_resume(mut self, resume: bool) -> Self {
+        self.resume = resume;
+        self
+    }
+
+    /// Encode the chunks of a copied array with different codecs. Chunks are
+    /// only decoded when the codecs differ from the source array.
+    pub fn with_codecs(mut self, codecs: Vec<Extension>) -> Self {
+        self.codecs = Some(codecs);
+        self
+    }
+}
+
+/// The number of keys written and skipped by a copy
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CopyReport {
+    pub copied: usize,
+    pub skipped: usize,
+}
+
+impl CopyReport {
+    pub(crate) fn from_copied(copied: impl IntoIterator<Item = bool>) -> Self {
+        copied.into_iter().fold(Self::default(), |report, copied| Self {
+            copied: report.copied + copied as usize,
+            skipped: report.skipped + !copied as usize,
+        })
+    }
+}
+
+/// Copy every key under a prefix to a prefix of another store without
+/// decoding the values. Metadata is copied last, so an interrupted copy is not
+/// mistaken for a complete node, and never skipped when resuming since it can
+/// change without changing size.
+pub(crate) async fn copy_prefix<S, D>(
+    src: &S,
+    src_prefix: &str,
+    dst: &D,
+    dst_prefix: &str,
+    options: &CopyOptions,
+) -> Result<CopyReport, CharizarrError>
+where
+    S: ReadableStore + ListableStore,
+    D: ReadableStore + WriteableStore,
+{
+    let (metadata, chunks): (Vec<_>, Vec<_>) = src
+        .list_prefix(src_prefix)
+        .await?
+        .into_iter()
+        .partition(|key| key.ends_with("zarr.json"));
+
+    let mut copied = Vec::new();
+    for (keys, resume) in [(chunks, options.resume), (metadata, false)] {
+        let batch: Vec<bool> = stream::iter(keys.iter().map(|key| {
+            let to = format!("{dst_prefix}{}", key.strip_prefix(src_prefix).unwrap_or(key));
+            async move { copy_key(src, key, dst, &to, resume).await }
+        }))
+        .buffer_unordered(options.concurrency)
+        .try_collect()
+        .await?;
+        copied.extend(batch);
+    }
+    Ok(CopyReport::from_copied(copied))
+}
+
+/// Copy a single value between stores, returning false if it was skipped
+/// because the destination already holds a value of the same size
+async fn copy_key<S, D>(src: &S, from: &str, dst: &D, to: &str, resume: bool) -> Result<bool, CharizarrError>
+where
+    S: ReadableStore,
+    D: ReadableStore + WriteableStore,
+{
+    if resume && dst_has_size(dst, to, src.get_size(from).await?).await? {
+        return Ok(false);
+    }
+    let value = src.get(from).await?;
+    dst.set(to, &value).await?;
+    Ok(true)
+}
+
+/// Whether the destination holds a value of the given size at a key
+pub(crate) async fn dst_has_size<D>(dst: &D, key: &str, size: usize) -> Result<bool, CharizarrError>
+where
+    D: ReadableStore,
+{
+    match dst.get_size(key).await {
+        Ok(existing) => Ok(existing == size),
+        Err(CharizarrError::KeyNotFoundError(_)) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::error::CharizarrError;
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum CoreDataType {
     #[serde(rename = "bool")]
@@ -20,6 +22,10 @@ pub enum CoreDataType {
     UInt32,
     #[serde(rename = "uint64")]
     UInt64,
+    #[serde(rename = "float16")]
+    Float16,
+    #[serde(rename = "bfloat16")]
+    BFloat16,
     #[serde(rename = "float32")]
     Float32,
     #[serde(rename = "float64")]
@@ -32,4 +38,64 @@ pub enum CoreDataType {
     Raw8,
     #[serde(rename = "r16")]
     Raw16,
+    #[serde(rename = "string")]
+    String,
+    #[serde(rename = "bytes")]
+    Bytes,
+}
+
+impl CoreDataType {
+    /// Parse a data type from either its zarr name, such as `int16`, or a numpy
+    /// type string, such as `<i2`. Byte order in numpy type strings is ignored.
+    pub fn parse(name: &str) -> Result<Self, CharizarrError> {
+        if let Ok(dtype) = serde_json::from_value::<CoreDataType>(name.into()) {
+            return Ok(dtype);
+        }
+
+        let typestr = name.trim_start_matches(['<', '>', '|', '=']);
+        let dtype = match typestr {
+            "?" | "b1" => CoreDataType::Bool,
+            "i1" => CoreDataType::Int8,
+            "i2" => CoreDataType::Int16,
+            "i4" => CoreDataType::Int32,
+            "i8" => CoreDataType::Int64,
+            "u1" => CoreDataType::UInt8,
+            "u2" => CoreDataType::UInt16,
+            "u4" => CoreDataType::UInt32,
+            "u8" => CoreDataType::UInt64,
+            "f2" => CoreDataType::Float16,
+            "f4" => CoreDataType::Float32,
+            "f8" => CoreDataType::Float64,
+            "c8" => CoreDataType::Complex64,
+            "c16" => CoreDataType::Complex128,
+            _ => return Err(CharizarrError::TypeError(name.to_string())),
+        };
+        Ok(dtype)
+    }
+
+    /// The size of a single element of this data type in bytes, or None for
+    /// variable length data types
+    pub fn size(&self) -> Option<usize> {
+        let size = match self {
+            CoreDataType::Bool => 1,
+            CoreDataType::Int8 => 1,
+            CoreDataType::Int16 => 2,
+            CoreDataType::Int32 => 4,
+            CoreDataType::Int64 => 8,
+            CoreDataType::UInt8 => 1,
+            CoreDataType::UInt16 => 2,
+            CoreDataType::UInt32 => 4,
+            CoreDataType::UInt64 => 8,
+            CoreDataType::Float16 => 2,
+            CoreDataType::BFloat16 => 2,
+            CoreDataType::Float32 => 4,
+            CoreDataType::Float64 => 8,
+            CoreDataType::Complex64 => 8,
+            CoreDataType::Complex128 => 16,
+            CoreDataType::Raw8 => 1,
+            CoreDataType::Raw16 => 2,
+            CoreDataType::String | CoreDataType::Bytes => return None,
+        };
+        Some(size)
+    }
 }
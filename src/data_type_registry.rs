@@ -0,0 +1,237 @@
+use std::{collections::HashMap, sync::Arc};
+
+use serde_json::Value;
+
+use crate::{
+    codec::ByteToArrayCodec,
+    codecs::bytes::BytesCodec,
+    error::CharizarrError,
+    metadata::{DataType, Extension},
+    zarray::ZArray,
+};
+
+/// A user defined extension data type. Values are held in memory as a `ZArray`
+/// of the array data type, and the remaining methods describe how to convert
+/// between the metadata and that representation.
+pub trait ExtensionDataType: Send + Sync {
+    /// The name of the data type as it appears in the array metadata
+    fn resolve_name(&self) -> String;
+
+    /// The size of a single element in bytes, or None for variable length
+    /// data types
+    fn size(&self, config: &Value) -> Option<usize>;
+
+    /// The data type of the `ZArray` used to hold values of this data type,
+    /// which is the data type array to array codecs receive
+    fn array_data_type(&self, config: &Value) -> Result<DataType, CharizarrError>;
+
+    /// Convert the fill value stored in the metadata to a fill value of the
+    /// array data type
+    fn parse_fill_value(
+        &self,
+        _config: &Value,
+        fill_value: &Value,
+    ) -> Result<Value, CharizarrError> {
+        Ok(fill_value.clone())
+    }
+
+    /// Encode an array with the `bytes` codec. Defaults to the encoding of
+    /// the array data type.
+    fn encode_bytes(
+        &self,
+        config: &Value,
+        bytes_config: &Value,
+        data: &ZArray,
+    ) -> Result<Vec<u8>, CharizarrError> {
+        BytesCodec::new().encode(&self.array_data_type(config)?, bytes_config, data)
+    }
+
+    /// Decode an array with the `bytes` codec. Defaults to the encoding of
+    /// the array data type.
+    fn decode_bytes(
+        &self,
+        config: &Value,
+        bytes_config: &Value,
+        data: &[u8],
+    ) -> Result<ZArray, CharizarrError> {
+        BytesCodec::new().decode(&self.array_data_type(config)?, bytes_config, data)
+    }
+}
+
+/// Registered extension data types, looked up by the name in the array
+/// metadata. Core data types and the data types built into the crate do not
+/// need to be registered.
+#[derive(Clone, Default)]
+pub struct DataTypeRegistry {
+    data_types: HashMap<String, Arc<dyn ExtensionDataType>>,
+}
+
+impl DataTypeRegistry {
+    pub fn register(mut self, data_type: Arc<dyn ExtensionDataType>) -> Self {
+        self.data_types.insert(data_type.resolve_name(), data_type);
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Arc<dyn ExtensionDataType>> {
+        self.data_types.get(name)
+    }
+
+    /// Find the registered extension for a data type, along with its
+    /// configuration
+    pub fn resolve<'a>(
+        &'a self,
+        data_type: &'a DataType,
+    ) -> Option<(&'a Arc<dyn ExtensionDataType>, &'a Value)> {
+        let DataType::Extension(Extension {
+            name,
+            configuration,
+        }) = data_type
+        else {
+            return None;
+        };
+        self.get(name).map(|ext| (ext, configuration))
+    }
+
+    /// The size of a single element in bytes, if it is known for this data type
+    pub fn size(&self, data_type: &DataType) -> Option<usize> {
+        match self.resolve(data_type) {
+            Some((ext, config)) => ext.size(config),
+            None => data_type.size(),
+        }
+    }
+
+    /// The data type of the `ZArray` holding values of this data type
+    pub fn array_data_type(&self, data_type: &DataType) -> Result<DataType, CharizarrError> {
+        match self.resolve(data_type) {
+            Some((ext, config)) => ext.array_data_type(config),
+            None => Ok(data_type.clone()),
+        }
+    }
+
+    /// Create an array filled with the fill value stored in the metadata
+    pub fn full(
+        &self,
+        data_type: &DataType,
+        shape: &[usize],
+        fill_value: &Value,
+    ) -> Result<ZArray, CharizarrError> {
+        match self.resolve(data_type) {
+            Some((ext, config)) => {
+                let fill_value = ext.parse_fill_value(config, fill_value)?;
+                ZArray::full(&ext.array_data_type(config)?, shape, &fill_value)
+            }
+            None => ZArray::full(data_type, shape, fill_value),
+        }
+    }
+
+    /// Create an array of zeros, as defined by the array data type
+    pub fn zeros(&self, data_type: &DataType, shape: &[usize]) -> Result<ZArray, CharizarrError> {
+        ZArray::zeros(&self.array_data_type(data_type)?, shape)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::prelude::*;
+    use serde_json::json;
+
+    use crate::data_type::CoreDataType;
+
+    use super::*;
+
+    /// Fixed length ascii strings, padded with null bytes
+    struct FixedAscii {}
+
+    impl ExtensionDataType for FixedAscii {
+        fn resolve_name(&self) -> String {
+            "fixed_ascii".to_string()
+        }
+
+        fn size(&self, config: &Value) -> Option<usize> {
+            config["length"].as_u64().map(|l| l as usize)
+        }
+
+        fn array_data_type(&self, _config: &Value) -> Result<DataType, CharizarrError> {
+            Ok(DataType::Core(CoreDataType::String))
+        }
+
+        fn parse_fill_value(
+            &self,
+            _config: &Value,
+            fill_value: &Value,
+        ) -> Result<Value, CharizarrError> {
+            match fill_value {
+                Value::Number(n) if n.as_u64() == Some(0) => Ok(json!("")),
+                _ => Ok(fill_value.clone()),
+            }
+        }
+
+        fn encode_bytes(
+            &self,
+            config: &Value,
+            _bytes_config: &Value,
+            data: &ZArray,
+        ) -> Result<Vec<u8>, CharizarrError> {
+            let length = self.size(config).unwrap();
+            let ZArray::String(arr) = data else {
+                return Err(CharizarrError::TypeError("string".to_string()));
+            };
+            Ok(arr
+                .iter()
+                .flat_map(|s| {
+                    let mut b = s.as_bytes().to_vec();
+                    b.resize(length, 0);
+                    b
+                })
+                .collect())
+        }
+
+        fn decode_bytes(
+            &self,
+            config: &Value,
+            _bytes_config: &Value,
+            data: &[u8],
+        ) -> Result<ZArray, CharizarrError> {
+            let length = self.size(config).unwrap();
+            let strings = data
+                .chunks(length)
+                .map(|b| {
+                    String::from_utf8_lossy(b)
+                        .trim_end_matches('\0')
+                        .to_string()
+                })
+                .collect::<Vec<String>>();
+            Ok(strings.into())
+        }
+    }
+
+    #[test]
+    fn test_data_type_registry() {
+        let registry = DataTypeRegistry::default().register(Arc::new(FixedAscii {}));
+        let data_type = DataType::Extension(Extension {
+            name: "fixed_ascii".to_string(),
+            configuration: json!({"length": 4}),
+        });
+
+        assert_eq!(registry.size(&data_type), Some(4));
+        assert_eq!(
+            registry.array_data_type(&data_type).unwrap(),
+            DataType::Core(CoreDataType::String)
+        );
+        let filled = registry.full(&data_type, &[2], &json!(0)).unwrap();
+        assert_eq!(
+            filled,
+            ZArray::String(Array::from_elem(2, "".to_string()).into_dyn())
+        );
+
+        let core = DataType::Core(CoreDataType::Int16);
+        assert_eq!(registry.size(&core), Some(2));
+        assert_eq!(registry.array_data_type(&core).unwrap(), core);
+
+        let unknown = DataType::Extension(Extension {
+            name: "unknown".to_string(),
+            configuration: json!({}),
+        });
+        assert!(registry.zeros(&unknown, &[2]).is_err());
+    }
+}
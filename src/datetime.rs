@@ -0,0 +1,318 @@
+use chrono::{Datelike, Months, NaiveDate, NaiveDateTime, TimeDelta};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    error::CharizarrError,
+    fill_value::FillValue,
+    metadata::{DataType, Extension},
+};
+
+/// The tick value numpy uses to represent NaT, not a time
+pub const NAT: i64 = i64::MIN;
+
+const NANOS_PER_SECOND: i128 = 1_000_000_000;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum TimeUnit {
+    #[serde(rename = "Y")]
+    Year,
+    #[serde(rename = "M")]
+    Month,
+    #[serde(rename = "W")]
+    Week,
+    #[serde(rename = "D")]
+    Day,
+    #[serde(rename = "h")]
+    Hour,
+    #[serde(rename = "m")]
+    Minute,
+    #[serde(rename = "s")]
+    Second,
+    #[serde(rename = "ms")]
+    Millisecond,
+    #[serde(rename = "us", alias = "μs")]
+    Microsecond,
+    #[serde(rename = "ns")]
+    Nanosecond,
+    #[serde(rename = "ps")]
+    Picosecond,
+    #[serde(rename = "fs")]
+    Femtosecond,
+    #[serde(rename = "as")]
+    Attosecond,
+    #[serde(rename = "generic")]
+    Generic,
+}
+
+impl TimeUnit {
+    /// The length of a tick in nanoseconds as a fraction, or None for units
+    /// that do not have a fixed length
+    fn nanos_ratio(&self) -> Option<(i128, i128)> {
+        let ratio = match self {
+            TimeUnit::Week => (7 * 86_400 * NANOS_PER_SECOND, 1),
+            TimeUnit::Day => (86_400 * NANOS_PER_SECOND, 1),
+            TimeUnit::Hour => (3_600 * NANOS_PER_SECOND, 1),
+            TimeUnit::Minute => (60 * NANOS_PER_SECOND, 1),
+            TimeUnit::Second => (NANOS_PER_SECOND, 1),
+            TimeUnit::Millisecond => (1_000_000, 1),
+            TimeUnit::Microsecond => (1_000, 1),
+            TimeUnit::Nanosecond => (1, 1),
+            TimeUnit::Picosecond => (1, 1_000),
+            TimeUnit::Femtosecond => (1, 1_000_000),
+            TimeUnit::Attosecond => (1, 1_000_000_000),
+            TimeUnit::Year | TimeUnit::Month | TimeUnit::Generic => return None,
+        };
+        Some(ratio)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimeKind {
+    DateTime64,
+    TimeDelta64,
+}
+
+impl TimeKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TimeKind::DateTime64 => "numpy.datetime64",
+            TimeKind::TimeDelta64 => "numpy.timedelta64",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "numpy.datetime64" => Some(TimeKind::DateTime64),
+            "numpy.timedelta64" => Some(TimeKind::TimeDelta64),
+            _ => None,
+        }
+    }
+}
+
+fn default_scale_factor() -> u32 {
+    1
+}
+
+/// The configuration of the numpy datetime64 and timedelta64 extension data
+/// types. Each stored i64 tick is `scale_factor` units long.
+///
+/// Adapted from https://github.com/zarr-developers/zarr-extensions/tree/main/data-types/numpy.datetime64
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct TimeConfig {
+    pub unit: TimeUnit,
+    #[serde(default = "default_scale_factor")]
+    pub scale_factor: u32,
+}
+
+impl TimeConfig {
+    pub fn new(unit: TimeUnit, scale_factor: u32) -> Self {
+        Self { unit, scale_factor }
+    }
+
+    fn epoch() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(1970, 1, 1)
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .expect("The unix epoch is a valid date")
+    }
+
+    /// Convert ticks to a duration, or None for NaT, calendar units and
+    /// values out of range
+    pub fn to_timedelta(&self, ticks: i64) -> Option<TimeDelta> {
+        if ticks == NAT {
+            return None;
+        }
+
+        let (num, den) = self.unit.nanos_ratio()?;
+        let nanos = (ticks as i128 * self.scale_factor as i128)
+            .checked_mul(num)?
+            .div_euclid(den);
+        let secs = i64::try_from(nanos.div_euclid(NANOS_PER_SECOND)).ok()?;
+        TimeDelta::new(secs, nanos.rem_euclid(NANOS_PER_SECOND) as u32)
+    }
+
+    /// Convert a duration to ticks, rounding towards negative infinity. None
+    /// maps to NaT.
+    pub fn from_timedelta(&self, delta: Option<TimeDelta>) -> Result<i64, CharizarrError> {
+        let Some(delta) = delta else {
+            return Ok(NAT);
+        };
+
+        let (num, den) = self.unit.nanos_ratio().ok_or_else(|| {
+            CharizarrError::TypeError(format!("{:?} ticks do not have a fixed length", self.unit))
+        })?;
+        let nanos = delta.num_seconds() as i128 * NANOS_PER_SECOND + delta.subsec_nanos() as i128;
+        let ticks = (nanos * den).div_euclid(num * self.scale_factor as i128);
+        self.checked_ticks(ticks)
+    }
+
+    /// Convert ticks to a point in time relative to the unix epoch, or None for
+    /// NaT and values out of range
+    pub fn to_datetime(&self, ticks: i64) -> Option<NaiveDateTime> {
+        if ticks == NAT {
+            return None;
+        }
+
+        let months = match self.unit {
+            TimeUnit::Year => (ticks as i128 * self.scale_factor as i128).checked_mul(12)?,
+            TimeUnit::Month => ticks as i128 * self.scale_factor as i128,
+            _ => return Self::epoch().checked_add_signed(self.to_timedelta(ticks)?),
+        };
+        let offset = Months::new(u32::try_from(months.unsigned_abs()).ok()?);
+        if months < 0 {
+            Self::epoch().checked_sub_months(offset)
+        } else {
+            Self::epoch().checked_add_months(offset)
+        }
+    }
+
+    /// Convert a point in time to ticks relative to the unix epoch, rounding
+    /// towards negative infinity. None maps to NaT.
+    pub fn from_datetime(&self, datetime: Option<NaiveDateTime>) -> Result<i64, CharizarrError> {
+        let Some(datetime) = datetime else {
+            return Ok(NAT);
+        };
+
+        let epoch = Self::epoch();
+        let months = match self.unit {
+            TimeUnit::Year | TimeUnit::Month => {
+                (datetime.year() as i128 - epoch.year() as i128) * 12 + datetime.month0() as i128
+            }
+            _ => return self.from_timedelta(Some(datetime - epoch)),
+        };
+        let months_per_tick = match self.unit {
+            TimeUnit::Year => 12,
+            _ => 1,
+        } * self.scale_factor as i128;
+        self.checked_ticks(months.div_euclid(months_per_tick))
+    }
+
+    fn checked_ticks(&self, ticks: i128) -> Result<i64, CharizarrError> {
+        i64::try_from(ticks)
+            .ok()
+            .filter(|t| *t != NAT)
+            .ok_or_else(|| {
+                CharizarrError::ArrayError(format!("{ticks} is out of range for {self:?}"))
+            })
+    }
+}
+
+/// A numpy datetime64 or timedelta64 extension data type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimeDataType {
+    pub kind: TimeKind,
+    pub config: TimeConfig,
+}
+
+impl TimeDataType {
+    pub fn new(kind: TimeKind, config: TimeConfig) -> Self {
+        Self { kind, config }
+    }
+
+    /// Parse the time data type from an extension data type, returning None if
+    /// it is not a datetime64 or timedelta64 data type
+    pub fn from_data_type(data_type: &DataType) -> Result<Option<Self>, CharizarrError> {
+        let DataType::Extension(ext) = data_type else {
+            return Ok(None);
+        };
+        let Some(kind) = TimeKind::from_name(&ext.name) else {
+            return Ok(None);
+        };
+
+        let config = serde_json::from_value::<TimeConfig>(ext.configuration.clone())
+            .map_err(|e| CharizarrError::TypeError(format!("{}: {e}", ext.name)))?;
+        if config.scale_factor == 0 {
+            return Err(CharizarrError::TypeError(format!(
+                "{}: scale_factor must be positive",
+                ext.name
+            )));
+        }
+        Ok(Some(Self { kind, config }))
+    }
+
+    pub fn to_data_type(&self) -> DataType {
+        DataType::Extension(Extension {
+            name: self.kind.name().to_string(),
+            configuration: serde_json::to_value(self.config)
+                .expect("Time config is always valid json"),
+        })
+    }
+
+    /// Parse a fill value, which is either an integer tick count or "NaT"
+    pub fn fill_value(&self, value: &Value) -> Option<i64> {
+        match value {
+            Value::String(s) if s == "NaT" => Some(NAT),
+            _ => i64::from_fill_value(value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Timelike;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_time_data_type() {
+        let data_type: DataType = serde_json::from_value(json!({
+            "name": "numpy.datetime64",
+            "configuration": {"unit": "s", "scale_factor": 10}
+        }))
+        .unwrap();
+        let time = TimeDataType::from_data_type(&data_type).unwrap().unwrap();
+        assert_eq!(time.kind, TimeKind::DateTime64);
+        assert_eq!(time.config, TimeConfig::new(TimeUnit::Second, 10));
+        assert_eq!(time.to_data_type(), data_type);
+        assert_eq!(time.fill_value(&json!("NaT")), Some(NAT));
+        assert_eq!(time.fill_value(&json!(3)), Some(3));
+
+        let data_type: DataType = serde_json::from_value(json!({
+            "name": "numpy.timedelta64",
+            "configuration": {"unit": "μs"}
+        }))
+        .unwrap();
+        let time = TimeDataType::from_data_type(&data_type).unwrap().unwrap();
+        assert_eq!(time.config, TimeConfig::new(TimeUnit::Microsecond, 1));
+
+        let data_type = DataType::Core(crate::data_type::CoreDataType::Int64);
+        assert_eq!(TimeDataType::from_data_type(&data_type).unwrap(), None);
+    }
+
+    #[test]
+    fn test_time_conversions() {
+        let config = TimeConfig::new(TimeUnit::Second, 10);
+        let datetime = NaiveDate::from_ymd_opt(2024, 2, 29)
+            .unwrap()
+            .and_hms_opt(12, 0, 5)
+            .unwrap();
+        let ticks = config.from_datetime(Some(datetime)).unwrap();
+        assert_eq!(ticks, 170_920_800);
+        assert_eq!(config.to_datetime(ticks), datetime.with_second(0));
+        assert_eq!(config.to_datetime(NAT), None);
+        assert_eq!(config.from_datetime(None).unwrap(), NAT);
+
+        let config = TimeConfig::new(TimeUnit::Month, 1);
+        assert_eq!(config.from_datetime(Some(datetime)).unwrap(), 649);
+        assert_eq!(
+            config.to_datetime(-1),
+            NaiveDate::from_ymd_opt(1969, 12, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+        );
+
+        let config = TimeConfig::new(TimeUnit::Nanosecond, 1);
+        assert_eq!(config.to_timedelta(-1), Some(TimeDelta::nanoseconds(-1)));
+        assert_eq!(
+            config
+                .from_timedelta(Some(TimeDelta::milliseconds(-3)))
+                .unwrap(),
+            -3_000_000
+        );
+
+        let config = TimeConfig::new(TimeUnit::Picosecond, 1);
+        assert_eq!(config.to_timedelta(1_500), Some(TimeDelta::nanoseconds(1)));
+        assert!(TimeConfig::new(TimeUnit::Year, 1).to_timedelta(1).is_none());
+    }
+}
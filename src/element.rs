@@ -0,0 +1,87 @@
+use half::{bf16, f16};
+use ndarray::ArrayD;
+use num::Complex;
+
+use crate::{data_type::CoreDataType, error::CharizarrError, metadata::DataType, zarray::ZArray};
+
+/// A rust type that holds a single element of a core data type
+pub trait Element: Clone + Send + Sync + 'static {
+    /// The core data type of the element
+    fn data_type() -> CoreDataType;
+
+    /// Take the typed array out of a `ZArray`, erroring if the `ZArray` holds
+    /// a different data type
+    fn from_zarray(arr: ZArray) -> Result<ArrayD<Self>, CharizarrError>;
+
+    /// Wrap a typed array in a `ZArray`
+    fn into_zarray(arr: ArrayD<Self>) -> ZArray;
+
+    /// Whether arrays of the given data type can be read as this element
+    fn matches(data_type: &DataType) -> bool {
+        matches!(data_type, DataType::Core(core) if core == &Self::data_type())
+    }
+
+    /// View elements as their bytes in native byte order, for elements where
+    /// every bit pattern is a valid value so codecs can decode into them
+    fn as_bytes_mut(_values: &mut [Self]) -> Option<&mut [u8]> {
+        None
+    }
+}
+
+macro_rules! element {
+    ($d_name:path, $core:path, $d_type:ty) => {
+        impl Element for $d_type {
+            fn data_type() -> CoreDataType {
+                $core
+            }
+
+            fn from_zarray(arr: ZArray) -> Result<ArrayD<Self>, CharizarrError> {
+                arr.try_into()
+            }
+
+            fn into_zarray(arr: ArrayD<Self>) -> ZArray {
+                $d_name(arr)
+            }
+        }
+    };
+    ($d_name:path, $core:path, $d_type:ty, plain) => {
+        impl Element for $d_type {
+            fn data_type() -> CoreDataType {
+                $core
+            }
+
+            fn from_zarray(arr: ZArray) -> Result<ArrayD<Self>, CharizarrError> {
+                arr.try_into()
+            }
+
+            fn into_zarray(arr: ArrayD<Self>) -> ZArray {
+                $d_name(arr)
+            }
+
+            fn as_bytes_mut(values: &mut [Self]) -> Option<&mut [u8]> {
+                let len = std::mem::size_of_val(values);
+                // SAFETY: the element is a plain number without padding, so
+                // any bytes written through the view are a valid value
+                Some(unsafe { std::slice::from_raw_parts_mut(values.as_mut_ptr() as *mut u8, len) })
+            }
+        }
+    };
+}
+
+element!(ZArray::Bool, CoreDataType::Bool, bool);
+element!(ZArray::Int8, CoreDataType::Int8, i8, plain);
+element!(ZArray::Int16, CoreDataType::Int16, i16, plain);
+element!(ZArray::Int32, CoreDataType::Int32, i32, plain);
+element!(ZArray::Int64, CoreDataType::Int64, i64, plain);
+element!(ZArray::UInt8, CoreDataType::UInt8, u8, plain);
+element!(ZArray::UInt16, CoreDataType::UInt16, u16, plain);
+element!(ZArray::UInt32, CoreDataType::UInt32, u32, plain);
+element!(ZArray::UInt64, CoreDataType::UInt64, u64, plain);
+element!(ZArray::Float16, CoreDataType::Float16, f16, plain);
+element!(ZArray::BFloat16, CoreDataType::BFloat16, bf16, plain);
+element!(ZArray::Float32, CoreDataType::Float32, f32, plain);
+element!(ZArray::Float64, CoreDataType::Float64, f64, plain);
+element!(ZArray::Complex64, CoreDataType::Complex64, Complex<f32>);
+element!(ZArray::Complex128, CoreDataType::Complex128, Complex<f64>);
+element!(ZArray::String, CoreDataType::String, String);
+element!(ZArray::Bytes, CoreDataType::Bytes, Vec<u8>);
@@ -10,6 +10,8 @@ pub enum CharizarrError {
     GroupError(String),
     #[error("Zarr store: {0}")]
     StoreError(String),
+    #[error("Key not found in store: {0}")]
+    KeyNotFoundError(String),
     #[error("Chunk is not of type {0}")]
     TypeError(String),
     #[error("Feature not implmented: {0}")]
@@ -0,0 +1,155 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use half::{bf16, f16};
+use num::Complex;
+use serde_json::Value;
+
+/// Conversion from the JSON fill value stored in the array metadata to a
+/// single element of an array.
+///
+/// Adapted from https://zarr-specs.readthedocs.io/en/latest/v3/core/v3.0.html#fill-value
+pub trait FillValue: Sized {
+    fn from_fill_value(value: &Value) -> Option<Self>;
+}
+
+impl FillValue for bool {
+    fn from_fill_value(value: &Value) -> Option<Self> {
+        value.as_bool()
+    }
+}
+
+macro_rules! int_fill_value {
+    ($d_type:ty) => {
+        impl FillValue for $d_type {
+            fn from_fill_value(value: &Value) -> Option<Self> {
+                if let Some(v) = value.as_i64() {
+                    return <$d_type>::try_from(v).ok();
+                }
+                value.as_u64().and_then(|v| <$d_type>::try_from(v).ok())
+            }
+        }
+    };
+}
+
+int_fill_value!(i8);
+int_fill_value!(i16);
+int_fill_value!(i32);
+int_fill_value!(i64);
+int_fill_value!(u8);
+int_fill_value!(u16);
+int_fill_value!(u32);
+int_fill_value!(u64);
+
+macro_rules! float_fill_value {
+    ($d_type:ty, $u_type:ty) => {
+        impl FillValue for $d_type {
+            fn from_fill_value(value: &Value) -> Option<Self> {
+                match value {
+                    Value::Number(n) => n.as_f64().map(|v| v as $d_type),
+                    Value::String(s) => match s.as_str() {
+                        "NaN" => Some(<$d_type>::NAN),
+                        "Infinity" => Some(<$d_type>::INFINITY),
+                        "-Infinity" => Some(<$d_type>::NEG_INFINITY),
+                        // Raw bits as a hex string, used for non-default NaNs
+                        hex => hex
+                            .strip_prefix("0x")
+                            .and_then(|h| <$u_type>::from_str_radix(h, 16).ok())
+                            .map(<$d_type>::from_bits),
+                    },
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+float_fill_value!(f32, u32);
+float_fill_value!(f64, u64);
+
+macro_rules! half_fill_value {
+    ($d_type:ty) => {
+        impl FillValue for $d_type {
+            fn from_fill_value(value: &Value) -> Option<Self> {
+                match value {
+                    Value::Number(n) => n.as_f64().map(<$d_type>::from_f64),
+                    Value::String(s) => match s.as_str() {
+                        "NaN" => Some(<$d_type>::NAN),
+                        "Infinity" => Some(<$d_type>::INFINITY),
+                        "-Infinity" => Some(<$d_type>::NEG_INFINITY),
+                        hex => hex
+                            .strip_prefix("0x")
+                            .and_then(|h| u16::from_str_radix(h, 16).ok())
+                            .map(<$d_type>::from_bits),
+                    },
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+half_fill_value!(f16);
+half_fill_value!(bf16);
+
+impl<T: FillValue> FillValue for Complex<T> {
+    fn from_fill_value(value: &Value) -> Option<Self> {
+        let parts = value.as_array()?;
+        let [re, im] = parts.as_slice() else {
+            return None;
+        };
+        Some(Complex::new(
+            T::from_fill_value(re)?,
+            T::from_fill_value(im)?,
+        ))
+    }
+}
+
+impl FillValue for String {
+    fn from_fill_value(value: &Value) -> Option<Self> {
+        value.as_str().map(|s| s.to_string())
+    }
+}
+
+impl FillValue for Vec<u8> {
+    /// Variable length bytes are stored either as a base64 encoded string, as
+    /// written by zarr-python, or as an array of byte values
+    fn from_fill_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::String(s) => STANDARD.decode(s).ok(),
+            Value::Array(values) => values.iter().map(u8::from_fill_value).collect(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_fill_values() {
+        assert_eq!(i16::from_fill_value(&json!(-3)), Some(-3));
+        assert_eq!(u8::from_fill_value(&json!(300)), None);
+        assert_eq!(u64::from_fill_value(&json!(u64::MAX)), Some(u64::MAX));
+        assert_eq!(bool::from_fill_value(&json!(true)), Some(true));
+        assert_eq!(f64::from_fill_value(&json!(1.5)), Some(1.5));
+        assert!(f32::from_fill_value(&json!("NaN")).unwrap().is_nan());
+        assert_eq!(
+            f64::from_fill_value(&json!("-Infinity")),
+            Some(f64::NEG_INFINITY)
+        );
+        assert_eq!(f32::from_fill_value(&json!("0x3f800000")), Some(1.0));
+        assert_eq!(f16::from_fill_value(&json!(0.5)), Some(f16::from_f32(0.5)));
+        assert_eq!(f16::from_fill_value(&json!("0x3c00")), Some(f16::ONE));
+        assert!(bf16::from_fill_value(&json!("NaN")).unwrap().is_nan());
+        assert_eq!(
+            Complex::<f32>::from_fill_value(&json!([1.0, "NaN"])).map(|c| c.re),
+            Some(1.0)
+        );
+        assert_eq!(String::from_fill_value(&json!("")), Some("".to_string()));
+        assert_eq!(String::from_fill_value(&json!(0)), None);
+        assert_eq!(Vec::<u8>::from_fill_value(&json!("AQI=")), Some(vec![1, 2]));
+        assert_eq!(Vec::<u8>::from_fill_value(&json!([1, 2])), Some(vec![1, 2]));
+    }
+}
@@ -1,10 +1,11 @@
 use std::collections::HashMap;
 
+use futures::{stream, StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::{
-    array::{Array, ArrayMetadata}, codec_registry::CodecRegistry, error::CharizarrError, metadata::{DataType, Extension, NodeType, ZarrFormat}, store::{ListableStore, ReadableStore, WriteableStore}
+    array::{Array, ArrayMetadata, DEFAULT_CONCURRENCY}, codec_registry::CodecRegistry, copy::{copy_prefix, CopyOptions, CopyReport}, error::CharizarrError, metadata::{DataType, Extension, NodeType, ZarrFormat}, store::{erase_node, move_key, ListableStore, ReadableStore, WriteableStore}
 };
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -29,6 +30,7 @@ where
     T: ReadableStore + ListableStore + WriteableStore,
 {
     store: &'a T,
+    concurrency: usize,
     pub metadata: GroupMetadata,
     pub path: String,
 }
@@ -48,6 +50,7 @@ where
 
         Ok(Self {
             store,
+            concurrency: DEFAULT_CONCURRENCY,
             metadata,
             path,
         })
@@ -76,11 +79,24 @@ where
 
         Ok(Self {
             store,
+            concurrency: DEFAULT_CONCURRENCY,
             metadata,
             path,
         })
     }
 
+    /// Limit the number of keys that are moved at the same time. The limit is
+    /// at least one.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// The maximum number of keys that are moved at the same time
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+
     pub fn attrs(&self) -> &Option<HashMap<String, Value>> {
         &self.metadata.attributes
     }
@@ -108,13 +124,13 @@ where
     /// Get an child group from the group
     pub async fn get_group(&self, name: &str) -> Result<Group<'a, T>, CharizarrError> {
         let path = format!("{path}{name}", path = self.path);
-        Group::open(self.store, Some(path)).await
+        Ok(Group::open(self.store, Some(path)).await?.with_concurrency(self.concurrency))
     }
 
     /// Create a new child group in the group
     pub async fn create_group(&self, name: &str) -> Result<Group<'a, T>, CharizarrError> {
         let path = format!("{path}{name}", path = self.path);
-        Group::create(self.store, Some(path), None).await
+        Ok(Group::create(self.store, Some(path), None).await?.with_concurrency(self.concurrency))
     }
 
     /// Create a new child array in the group
@@ -135,6 +151,106 @@ where
         Array::create(self.store, Some(path), codec_registry, shape, chunk_shape, chunk_key_encoding, data_type, fill_value, codecs, dimension_names, attributes).await
     }
 
+    /// Erase a child array or group, including everything nested under it.
+    /// With `dry_run` nothing is erased. Returns the keys that were, or would
+    /// be, erased.
+    pub async fn delete_member(&self, name: &str, dry_run: bool) -> Result<Vec<String>, CharizarrError> {
+        let path = format!("{path}{name}/", path = self.path);
+        self.store.get(&format!("{path}zarr.json")).await?;
+        erase_node(self.store, &path, dry_run).await
+    }
+
+    /// Move a child array or group, including everything nested under it, to
+    /// another path relative to the group. Missing groups above the
+    /// destination are created. Errors without changing anything if the
+    /// destination already exists or would be nested under an array.
+    pub async fn move_member(&self, src: &str, dst: &str) -> Result<(), CharizarrError> {
+        let src_path = format!("{path}{src}/", path = self.path);
+        let dst_path = format!("{path}{dst}/", path = self.path);
+        if dst_path.starts_with(&src_path) {
+            return Err(CharizarrError::GroupError(format!(
+                "Cannot move {src} into itself at {dst}"
+            )));
+        }
+
+        self.store.get(&format!("{src_path}zarr.json")).await?;
+        if !self.store.list_prefix(&dst_path).await?.is_empty() {
+            return Err(CharizarrError::GroupError(format!("{dst} already exists")));
+        }
+
+        // Every parent of the destination must be a group, so the moved node
+        // can be opened through them
+        let parts = dst.split('/').filter(|part| !part.is_empty()).collect::<Vec<_>>();
+        let mut missing = vec![];
+        for depth in 1..parts.len() {
+            let parent = parts[..depth].join("/");
+            let parent_path = format!("{path}{parent}", path = self.path);
+            match self.store.get(&format!("{parent_path}/zarr.json")).await {
+                Ok(raw_metadata) => {
+                    let is_group = serde_json::from_slice::<GroupMetadata>(&raw_metadata)
+                        .is_ok_and(|metadata| metadata.node_type == NodeType::Group);
+                    if !is_group {
+                        return Err(CharizarrError::GroupError(format!(
+                            "Cannot move {src} to {dst}, {parent} is not a group"
+                        )));
+                    }
+                }
+                Err(CharizarrError::KeyNotFoundError(_)) => missing.push(parent_path),
+                Err(e) => return Err(e),
+            }
+        }
+        for parent_path in missing {
+            Group::create(self.store, Some(parent_path), None).await?;
+        }
+
+        // Move the metadata last, so an interrupted move never leaves a node
+        // without its metadata or a destination that looks complete
+        let (metadata, chunks): (Vec<_>, Vec<_>) = self
+            .store
+            .list_prefix(&src_path)
+            .await?
+            .into_iter()
+            .partition(|key| key.ends_with("zarr.json"));
+        for keys in [chunks, metadata] {
+            stream::iter(keys.iter().map(|key| {
+                let to = format!("{dst_path}{}", key.strip_prefix(&src_path).unwrap_or(key));
+                async move { move_key(self.store, key, &to).await }
+            }))
+            .buffer_unordered(self.concurrency)
+            .try_collect::<()>()
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Copy the group and everything nested under it to a path in another
+    /// store, without decoding any chunks. See `CopyOptions` for resuming an
+    /// interrupted copy. Changing codecs is only supported when copying
+    /// arrays with `Array::copy_to`.
+    pub async fn copy_to<D>(
+        &self,
+        store: &D,
+        path: Option<String>,
+        options: &CopyOptions,
+    ) -> Result<CopyReport, CharizarrError>
+    where
+        D: ReadableStore + ListableStore + WriteableStore,
+    {
+        if options.codecs.is_some() {
+            return Err(CharizarrError::GroupError(
+                "Codecs can only be changed when copying an array".to_string(),
+            ));
+        }
+        let path = path.map_or_else(|| "".to_string(), |p| format!("{p}/"));
+        copy_prefix(self.store, &self.path, store, &path, options).await
+    }
+
+    /// Erase the group and everything nested under it. With `dry_run` nothing
+    /// is erased. Returns the keys that were, or would be, erased.
+    pub async fn delete(&self, dry_run: bool) -> Result<Vec<String>, CharizarrError> {
+        erase_node(self.store, &self.path, dry_run).await
+    }
+
     /// Add an attribute to the group
     pub async fn add_attr(&mut self, key: String, value: Value) -> Result<(), CharizarrError> {
         let mut attrs = self.metadata.attributes.take().unwrap_or_default();
@@ -1,8 +1,27 @@
 /// This is a port of https://github.com/scalableminds/zarrita/blob/async/zarrita/indexing.py
-use std::ops::Range;
+use std::{collections::BTreeMap, ops::Range};
 
 use itertools::{izip, Itertools, MultiProduct};
-use ndarray::Slice;
+use ndarray::{indices, ArrayD, ArrayViewD, Dimension, Slice};
+
+/// The part of a selection that falls within a single chunk, describing how
+/// to copy values between the chunk and the output array
+pub trait Projection {
+    /// The coordinates of the chunk in the chunk grid
+    fn chunk_coords(&self) -> &[usize];
+
+    /// The projection for writing into a chunk, which reads from the output
+    /// selection of the values and writes to the chunk selection
+    fn swapped(&self) -> Self;
+
+    /// Copy the chunk selection of the values into the output selection of
+    /// the array
+    fn assign<T: Clone>(&self, arr: &mut ArrayD<T>, value: ArrayViewD<T>);
+
+    /// Whether the chunk selection covers every item of a chunk with the
+    /// given shape, clipped to the bounds of the array
+    fn is_complete(&self, chunk_shape: &[usize]) -> bool;
+}
 
 #[derive(Debug, Clone)]
 pub struct ChunkIndexProjection {
@@ -11,9 +30,39 @@ pub struct ChunkIndexProjection {
     pub out_sel: Slice,
 }
 
+/// A normalized selection along a single dimension, with the bounds already
+/// resolved against the dimension length
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DimSelection {
+    pub range: Range<usize>,
+    pub step: usize,
+}
+
+impl DimSelection {
+    pub fn new(range: Range<usize>, step: usize) -> Self {
+        Self { range, step }
+    }
+
+    /// The number of items selected
+    pub fn nitems(&self) -> usize {
+        if self.range.end <= self.range.start {
+            0
+        } else {
+            (self.range.end - self.range.start).div_ceil(self.step)
+        }
+    }
+}
+
+impl From<Range<usize>> for DimSelection {
+    fn from(range: Range<usize>) -> Self {
+        Self::new(range, 1)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SliceDimIndexIterator {
     sel: Range<usize>,
+    step: usize,
     dim_len: usize,
     chunk_len: usize,
     nitems: usize,
@@ -21,11 +70,13 @@ pub struct SliceDimIndexIterator {
 }
 
 impl SliceDimIndexIterator {
-    pub fn new(dim_len: usize, chunk_len: usize, sel: Range<usize>) -> Self {
-        let nitems = (sel.end - sel.start).max(0);
-        let current_chunk_index = sel.start / chunk_len;
+    pub fn new(dim_len: usize, chunk_len: usize, sel: impl Into<DimSelection>) -> Self {
+        let sel = sel.into();
+        let nitems = sel.nitems();
+        let current_chunk_index = sel.range.start / chunk_len;
         Self {
-            sel,
+            sel: sel.range,
+            step: sel.step,
             dim_len,
             chunk_len,
             nitems,
@@ -38,41 +89,51 @@ impl Iterator for SliceDimIndexIterator {
     type Item = ChunkIndexProjection;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let dim_offset = self.current_chunk_index * self.chunk_len;
-        if dim_offset >= self.sel.end {
-            return None;
-        }
+        loop {
+            let dim_offset = self.current_chunk_index * self.chunk_len;
+            if dim_offset >= self.sel.end || dim_offset >= self.dim_len {
+                return None;
+            }
 
-        let dim_limit = ((self.current_chunk_index + 1) * self.chunk_len).min(self.dim_len);
+            let dim_limit = ((self.current_chunk_index + 1) * self.chunk_len).min(self.dim_len);
 
-        // determine chunk length, accounting for trailing chunk
-        let dim_chunk_len = dim_limit - dim_offset;
-        let (dim_chunk_sel_start, dim_out_offset) = if self.sel.start < dim_offset {
-            let dim_out_offset = dim_offset - self.sel.start;
-            (0usize, dim_out_offset)
-        } else {
-            let dim_chunk_sel_start = self.sel.start - dim_offset;
-            (dim_chunk_sel_start, 0usize)
-        };
+            // determine chunk length, accounting for trailing chunk
+            let dim_chunk_len = dim_limit - dim_offset;
+            let (dim_chunk_sel_start, dim_out_offset) = if self.sel.start < dim_offset {
+                // skip ahead to the first selected item in this chunk
+                let remainder = (dim_offset - self.sel.start) % self.step;
+                let dim_chunk_sel_start = if remainder > 0 { self.step - remainder } else { 0 };
+                let dim_out_offset = (dim_offset - self.sel.start).div_ceil(self.step);
+                (dim_chunk_sel_start, dim_out_offset)
+            } else {
+                let dim_chunk_sel_start = self.sel.start - dim_offset;
+                (dim_chunk_sel_start, 0usize)
+            };
 
-        let dim_chunk_sel_stop = if self.sel.end > dim_limit {
-            dim_chunk_len
-        } else {
-            self.sel.end - dim_offset
-        };
+            let dim_chunk_sel_stop = if self.sel.end > dim_limit {
+                dim_chunk_len
+            } else {
+                self.sel.end - dim_offset
+            };
 
-        let chunk_sel = Slice::new(dim_chunk_sel_start as isize, Some(dim_chunk_sel_stop as isize), 1);
-        let chunk_nitems = dim_chunk_sel_stop - dim_chunk_sel_start;
-        let out_sel = Slice::new(dim_out_offset as isize, Some(dim_out_offset as isize + chunk_nitems as isize), 1);
+            let chunk_index = self.current_chunk_index;
+            self.current_chunk_index += 1;
 
-        let chunk_index = self.current_chunk_index;
-        self.current_chunk_index += 1;
+            // with large steps a chunk may not contain any selected items
+            if dim_chunk_sel_start >= dim_chunk_sel_stop {
+                continue;
+            }
 
-        Some(ChunkIndexProjection {
-            chunk_index,
-            chunk_sel,
-            out_sel,
-        })
+            let chunk_sel = Slice::new(dim_chunk_sel_start as isize, Some(dim_chunk_sel_stop as isize), self.step as isize);
+            let chunk_nitems = (dim_chunk_sel_stop - dim_chunk_sel_start).div_ceil(self.step);
+            let out_sel = Slice::new(dim_out_offset as isize, Some(dim_out_offset as isize + chunk_nitems as isize), 1);
+
+            return Some(ChunkIndexProjection {
+                chunk_index,
+                chunk_sel,
+                out_sel,
+            });
+        }
     }
 }
 
@@ -83,23 +144,54 @@ pub struct ChunkProjection {
     pub out_sel: Vec<Slice>,
 }
 
+impl Projection for ChunkProjection {
+    fn chunk_coords(&self) -> &[usize] {
+        &self.chunk_coords
+    }
+
+    fn swapped(&self) -> Self {
+        Self {
+            chunk_coords: self.chunk_coords.clone(),
+            chunk_sel: self.out_sel.clone(),
+            out_sel: self.chunk_sel.clone(),
+        }
+    }
+
+    fn assign<T: Clone>(&self, arr: &mut ArrayD<T>, value: ArrayViewD<T>) {
+        let target = value.slice_each_axis(|a| self.chunk_sel[a.axis.0]);
+        let mut arr_view = arr.slice_each_axis_mut(|a| self.out_sel[a.axis.0]);
+        arr_view.assign(&target);
+    }
+
+    fn is_complete(&self, chunk_shape: &[usize]) -> bool {
+        self.chunk_sel.iter().zip(chunk_shape).all(|(sel, &len)| {
+            sel.start == 0 && sel.end == Some(len as isize) && (sel.step == 1 || len == 1)
+        })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct BasicIndexIterator {
     indexes: MultiProduct<SliceDimIndexIterator>,
+    // An array with no dimensions has a single chunk, which the product of no
+    // dimensions does not yield
+    scalar: bool,
     pub shape: Vec<usize>,
 }
 
 impl BasicIndexIterator {
-    pub fn new(shape: Vec<usize>, chunk_shape: Vec<usize>, sel: Vec<Range<usize>>) -> Self {
+    pub fn new(shape: Vec<usize>, chunk_shape: Vec<usize>, sel: Vec<impl Into<DimSelection>>) -> Self {
         let indexes = izip!(shape, chunk_shape, sel)
             .map(|(dim_len, chunk_len, sel)| SliceDimIndexIterator::new(dim_len, chunk_len, sel))
             .collect::<Vec<_>>();
         let sel_shape = indexes.iter().map(|indexer| indexer.nitems).collect();
 
+        let scalar = indexes.is_empty();
         let indexes = indexes.into_iter().multi_cartesian_product();
 
         Self {
             indexes,
+            scalar,
             shape: sel_shape,
         }
     }
@@ -109,6 +201,14 @@ impl Iterator for BasicIndexIterator {
     type Item = ChunkProjection;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.scalar {
+            self.scalar = false;
+            return Some(ChunkProjection {
+                chunk_coords: vec![],
+                chunk_sel: vec![],
+                out_sel: vec![],
+            });
+        }
         let chunk_indexes = self.indexes.next()?;
 
         let chunk_coords = chunk_indexes
@@ -117,11 +217,11 @@ impl Iterator for BasicIndexIterator {
             .collect();
         let chunk_sel = chunk_indexes
             .iter()
-            .map(|index| index.chunk_sel.clone())
+            .map(|index| index.chunk_sel)
             .collect();
         let out_sel = chunk_indexes
             .iter()
-            .map(|index| index.out_sel.clone())
+            .map(|index| index.out_sel)
             .collect();
 
         Some(ChunkProjection {
@@ -132,6 +232,241 @@ impl Iterator for BasicIndexIterator {
     }
 }
 
+/// The items of a single dimension selected from a chunk by an integer array
+#[derive(Debug, Clone)]
+pub struct ChunkDimPoints {
+    pub chunk_index: usize,
+    pub chunk_sel: Vec<usize>,
+    pub out_sel: Vec<usize>,
+}
+
+/// Groups the integer indices selected along a dimension by the chunk they
+/// fall in. The indices may be in any order and may repeat.
+#[derive(Debug, Clone)]
+pub struct IntDimIndexIterator {
+    chunks: std::vec::IntoIter<ChunkDimPoints>,
+    nitems: usize,
+}
+
+impl IntDimIndexIterator {
+    pub fn new(chunk_len: usize, sel: &[usize]) -> Self {
+        let mut chunks = BTreeMap::new();
+        for (out_index, index) in sel.iter().enumerate() {
+            let chunk_index = index / chunk_len;
+            let points = chunks.entry(chunk_index).or_insert_with(|| ChunkDimPoints {
+                chunk_index,
+                chunk_sel: vec![],
+                out_sel: vec![],
+            });
+            points.chunk_sel.push(index - chunk_index * chunk_len);
+            points.out_sel.push(out_index);
+        }
+
+        Self {
+            chunks: chunks.into_values().collect::<Vec<_>>().into_iter(),
+            nitems: sel.len(),
+        }
+    }
+}
+
+impl Iterator for IntDimIndexIterator {
+    type Item = ChunkDimPoints;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chunks.next()
+    }
+}
+
+/// The outer product of integer selections of each dimension that falls
+/// within a single chunk
+#[derive(Clone, Debug)]
+pub struct OrthogonalChunkProjection {
+    pub chunk_coords: Vec<usize>,
+    pub chunk_sel: Vec<Vec<usize>>,
+    pub out_sel: Vec<Vec<usize>>,
+}
+
+impl Projection for OrthogonalChunkProjection {
+    fn chunk_coords(&self) -> &[usize] {
+        &self.chunk_coords
+    }
+
+    fn swapped(&self) -> Self {
+        Self {
+            chunk_coords: self.chunk_coords.clone(),
+            chunk_sel: self.out_sel.clone(),
+            out_sel: self.chunk_sel.clone(),
+        }
+    }
+
+    fn assign<T: Clone>(&self, arr: &mut ArrayD<T>, value: ArrayViewD<T>) {
+        let lens = self.chunk_sel.iter().map(|sel| sel.len()).collect::<Vec<_>>();
+        let mut out_index = vec![0; lens.len()];
+        let mut chunk_index = vec![0; lens.len()];
+        for position in indices(lens) {
+            for (dim, &i) in position.slice().iter().enumerate() {
+                out_index[dim] = self.out_sel[dim][i];
+                chunk_index[dim] = self.chunk_sel[dim][i];
+            }
+            arr[out_index.as_slice()] = value[chunk_index.as_slice()].clone();
+        }
+    }
+
+    fn is_complete(&self, chunk_shape: &[usize]) -> bool {
+        self.chunk_sel.iter().zip(chunk_shape).all(|(sel, &len)| {
+            let mut selected = vec![false; len];
+            sel.iter().for_each(|&i| selected[i] = true);
+            selected.into_iter().all(|s| s)
+        })
+    }
+}
+
+/// Iterates the chunks touched by an orthogonal selection, where each
+/// dimension is selected by a list of integer indices. Every chunk is yielded
+/// once with all of the points selected from it.
+#[derive(Debug, Clone)]
+pub struct OrthogonalIndexIterator {
+    indexes: MultiProduct<IntDimIndexIterator>,
+    // An array with no dimensions has a single chunk, which the product of no
+    // dimensions does not yield
+    scalar: bool,
+    pub shape: Vec<usize>,
+}
+
+impl OrthogonalIndexIterator {
+    pub fn new(chunk_shape: Vec<usize>, sel: &[Vec<usize>]) -> Self {
+        let indexes = izip!(chunk_shape, sel)
+            .map(|(chunk_len, sel)| IntDimIndexIterator::new(chunk_len, sel))
+            .collect::<Vec<_>>();
+        let sel_shape = indexes.iter().map(|indexer| indexer.nitems).collect();
+
+        let scalar = indexes.is_empty();
+        let indexes = indexes.into_iter().multi_cartesian_product();
+
+        Self {
+            indexes,
+            scalar,
+            shape: sel_shape,
+        }
+    }
+}
+
+impl Iterator for OrthogonalIndexIterator {
+    type Item = OrthogonalChunkProjection;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.scalar {
+            self.scalar = false;
+            return Some(OrthogonalChunkProjection {
+                chunk_coords: vec![],
+                chunk_sel: vec![],
+                out_sel: vec![],
+            });
+        }
+        let chunk_indexes = self.indexes.next()?;
+
+        let chunk_coords = chunk_indexes
+            .iter()
+            .map(|index| index.chunk_index)
+            .collect();
+        let (chunk_sel, out_sel) = chunk_indexes
+            .into_iter()
+            .map(|index| (index.chunk_sel, index.out_sel))
+            .unzip();
+
+        Some(OrthogonalChunkProjection {
+            chunk_coords,
+            chunk_sel,
+            out_sel,
+        })
+    }
+}
+
+/// Individual points of a coordinate selection that fall within a single
+/// chunk. The chunk points are relative to the chunk.
+#[derive(Clone, Debug)]
+pub struct PointChunkProjection {
+    pub chunk_coords: Vec<usize>,
+    pub chunk_points: Vec<Vec<usize>>,
+    pub out_points: Vec<Vec<usize>>,
+}
+
+impl Projection for PointChunkProjection {
+    fn chunk_coords(&self) -> &[usize] {
+        &self.chunk_coords
+    }
+
+    fn swapped(&self) -> Self {
+        Self {
+            chunk_coords: self.chunk_coords.clone(),
+            chunk_points: self.out_points.clone(),
+            out_points: self.chunk_points.clone(),
+        }
+    }
+
+    fn assign<T: Clone>(&self, arr: &mut ArrayD<T>, value: ArrayViewD<T>) {
+        for (out_point, chunk_point) in self.out_points.iter().zip(&self.chunk_points) {
+            arr[out_point.as_slice()] = value[chunk_point.as_slice()].clone();
+        }
+    }
+
+    fn is_complete(&self, chunk_shape: &[usize]) -> bool {
+        let mut selected = ArrayD::from_elem(chunk_shape, false);
+        for point in &self.chunk_points {
+            selected[point.as_slice()] = true;
+        }
+        selected.iter().all(|s| *s)
+    }
+}
+
+/// Iterates the chunks touched by a coordinate selection, where the points
+/// are given as one list of coordinates per dimension. Every chunk is yielded
+/// once with all of the points that fall in it, and the points are placed
+/// in a one dimensional output in the order they were requested.
+#[derive(Debug, Clone)]
+pub struct CoordinateIndexIterator {
+    chunks: std::vec::IntoIter<PointChunkProjection>,
+    pub shape: Vec<usize>,
+}
+
+impl CoordinateIndexIterator {
+    pub fn new(chunk_shape: Vec<usize>, sel: &[Vec<usize>]) -> Self {
+        let npoints = sel.first().map_or(0, |coords| coords.len());
+
+        let mut chunks = BTreeMap::new();
+        for out_index in 0..npoints {
+            let point = sel.iter().map(|coords| coords[out_index]);
+            let (chunk_coords, chunk_point): (Vec<_>, Vec<_>) = point
+                .zip(&chunk_shape)
+                .map(|(index, chunk_len)| (index / chunk_len, index % chunk_len))
+                .unzip();
+
+            let projection = chunks
+                .entry(chunk_coords.clone())
+                .or_insert_with(|| PointChunkProjection {
+                    chunk_coords,
+                    chunk_points: vec![],
+                    out_points: vec![],
+                });
+            projection.chunk_points.push(chunk_point);
+            projection.out_points.push(vec![out_index]);
+        }
+
+        Self {
+            chunks: chunks.into_values().collect::<Vec<_>>().into_iter(),
+            shape: vec![npoints],
+        }
+    }
+}
+
+impl Iterator for CoordinateIndexIterator {
+    type Item = PointChunkProjection;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chunks.next()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -188,4 +523,112 @@ mod tests {
         assert_eq!(second_chunk.out_sel[0], Slice::new(1, Some(3), 1));
         assert_eq!(second_chunk.out_sel[1], Slice::new(0, Some(1), 1));
     }
+
+    #[test]
+    fn test_basic_index_iterator_scalar() {
+        // An array with no dimensions has a single chunk with an empty id
+        let chunks: Vec<_> = BasicIndexIterator::new(vec![], vec![], Vec::<Range<usize>>::new()).collect();
+        assert_eq!(chunks.len(), 1);
+        assert!(chunks[0].chunk_coords.is_empty());
+    }
+
+    #[test]
+    fn test_slice_dim_indexer_step() {
+        // Assuming dimensions of 10, chunk length of 3 and selection of 1..10 with step 4
+        // 0 [1] 2 | 3 4 [5] | 6 7 8 | [9]
+        let indexer = SliceDimIndexIterator::new(10, 3, DimSelection::new(1..10, 4));
+        assert_eq!(indexer.nitems, 3);
+
+        let chunks: Vec<_> = indexer.collect();
+        assert_eq!(chunks.len(), 3);
+
+        assert_eq!(chunks[0].chunk_index, 0);
+        assert_eq!(chunks[0].chunk_sel, Slice::new(1, Some(3), 4));
+        assert_eq!(chunks[0].out_sel, Slice::new(0, Some(1), 1));
+
+        assert_eq!(chunks[1].chunk_index, 1);
+        assert_eq!(chunks[1].chunk_sel, Slice::new(2, Some(3), 4));
+        assert_eq!(chunks[1].out_sel, Slice::new(1, Some(2), 1));
+
+        // chunk 2 holds no selected items so it is skipped
+        assert_eq!(chunks[2].chunk_index, 3);
+        assert_eq!(chunks[2].chunk_sel, Slice::new(0, Some(1), 4));
+        assert_eq!(chunks[2].out_sel, Slice::new(2, Some(3), 1));
+
+        let indexer = SliceDimIndexIterator::new(10, 3, 4..4);
+        assert_eq!(indexer.nitems, 0);
+        assert_eq!(indexer.count(), 0);
+    }
+
+    #[test]
+    fn test_orthogonal_index_iterator() {
+        // Assuming shape of (6, 4), chunk shape of (3, 2) and selection of [[4, 0, 5], [1]]
+        let chunks = OrthogonalIndexIterator::new(vec![3, 2], &[vec![4, 0, 5], vec![1]]);
+        assert_eq!(chunks.shape, vec![3, 1]);
+
+        let chunks: Vec<_> = chunks.collect();
+        assert_eq!(chunks.len(), 2);
+
+        // Rows 4 and 5 share a chunk, so it is only visited once
+        assert_eq!(chunks[0].chunk_coords, vec![0, 0]);
+        assert_eq!(chunks[0].chunk_sel, vec![vec![0], vec![1]]);
+        assert_eq!(chunks[0].out_sel, vec![vec![1], vec![0]]);
+
+        assert_eq!(chunks[1].chunk_coords, vec![1, 0]);
+        assert_eq!(chunks[1].chunk_sel, vec![vec![1, 2], vec![1]]);
+        assert_eq!(chunks[1].out_sel, vec![vec![0, 2], vec![0]]);
+
+        let mut out = ArrayD::zeros(vec![3, 1]);
+        let chunk = ndarray::Array::from_shape_vec(vec![3, 2], vec![0, 1, 2, 3, 4, 5]).unwrap();
+        chunks[1].assign(&mut out, chunk.view());
+        assert_eq!(out.into_raw_vec(), vec![3, 0, 5]);
+    }
+
+    #[test]
+    fn test_orthogonal_index_iterator_scalar() {
+        // An array with no dimensions has a single chunk with an empty id
+        let chunks: Vec<_> = OrthogonalIndexIterator::new(vec![], &[]).collect();
+        assert_eq!(chunks.len(), 1);
+        assert!(chunks[0].chunk_coords.is_empty());
+
+        let mut out = ArrayD::zeros(vec![]);
+        let chunk = ndarray::Array::from_elem(vec![], 7);
+        chunks[0].assign(&mut out, chunk.view());
+        assert_eq!(out.into_raw_vec(), vec![7]);
+    }
+
+    #[test]
+    fn test_coordinate_index_iterator() {
+        // Assuming shape of (6, 4), chunk shape of (3, 2) and points (4, 1), (0, 3), (5, 0)
+        let chunks = CoordinateIndexIterator::new(vec![3, 2], &[vec![4, 0, 5], vec![1, 3, 0]]);
+        assert_eq!(chunks.shape, vec![3]);
+
+        let chunks: Vec<_> = chunks.collect();
+        assert_eq!(chunks.len(), 2);
+
+        assert_eq!(chunks[0].chunk_coords, vec![0, 1]);
+        assert_eq!(chunks[0].chunk_points, vec![vec![0, 1]]);
+        assert_eq!(chunks[0].out_points, vec![vec![1]]);
+
+        // The first and last points share a chunk, so it is only visited once
+        assert_eq!(chunks[1].chunk_coords, vec![1, 0]);
+        assert_eq!(chunks[1].chunk_points, vec![vec![1, 1], vec![2, 0]]);
+        assert_eq!(chunks[1].out_points, vec![vec![0], vec![2]]);
+    }
+
+    #[test]
+    fn test_projection_is_complete() {
+        // A (5, 3) array with (2, 2) chunks, so the last chunk is clipped to (1, 1)
+        let chunks: Vec<_> = BasicIndexIterator::new(vec![5, 3], vec![2, 2], vec![0..5, 1..3]).collect();
+        assert!(!chunks[0].is_complete(&[2, 2]));
+        assert!(chunks[1].is_complete(&[2, 1]));
+        assert!(chunks[5].is_complete(&[1, 1]));
+
+        let chunks: Vec<_> = OrthogonalIndexIterator::new(vec![2, 2], &[vec![1, 0, 4], vec![2]]).collect();
+        assert!(chunks[0].is_complete(&[2, 1]));
+        assert!(chunks[1].is_complete(&[1, 1]));
+
+        let chunks: Vec<_> = CoordinateIndexIterator::new(vec![2, 2], &[vec![0, 1, 1], vec![0, 0, 1]]).collect();
+        assert!(!chunks[0].is_complete(&[2, 2]));
+    }
 }
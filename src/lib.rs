@@ -3,12 +3,20 @@ pub mod chunk;
 pub mod codec;
 pub mod codec_registry;
 pub mod codecs;
+pub mod copy;
 pub mod data_type;
+pub mod data_type_registry;
+pub mod datetime;
+pub mod element;
 pub mod error;
+pub mod fill_value;
 pub mod group;
+pub mod index;
 pub mod metadata;
+pub mod rechunk;
+pub mod selection;
 pub mod store;
 pub mod stores;
+pub mod structured;
+pub mod typed_array;
 pub mod zarray;
-
-mod index;
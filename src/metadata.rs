@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
-use crate::data_type::CoreDataType;
+use crate::{data_type::CoreDataType, datetime::TimeDataType, structured::StructuredDataType};
 
 pub type Configuration = Value;
 
@@ -36,6 +36,21 @@ pub enum DataType {
     Extension(Extension),
 }
 
+impl DataType {
+    /// The size of a single element in bytes, if it is known for this data type
+    pub fn size(&self) -> Option<usize> {
+        match self {
+            DataType::Core(core) => core.size(),
+            DataType::Extension(_) => {
+                if let Ok(Some(structured)) = StructuredDataType::from_data_type(self) {
+                    return Some(structured.itemsize);
+                }
+                TimeDataType::from_data_type(self).ok().flatten().map(|_| 8)
+            }
+        }
+    }
+}
+
 impl Display for DataType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -0,0 +1,141 @@
+use crate::metadata::Extension;
+
+/// The default memory budget for a single block of a rechunk, 256 MiB
+pub const DEFAULT_MAX_MEM: usize = 256 * 1024 * 1024;
+
+/// Options for rechunking an array into a new array
+#[derive(Debug, Clone)]
+pub struct RechunkOptions {
+    pub(crate) max_mem: usize,
+    pub(crate) codecs: Option<Vec<Extension>>,
+    pub(crate) element_size: Option<usize>,
+}
+
+impl Default for RechunkOptions {
+    fn default() -> Self {
+        Self {
+            max_mem: DEFAULT_MAX_MEM,
+            codecs: None,
+            element_size: None,
+        }
+    }
+}
+
+impl RechunkOptions {
+    /// Limit the memory in bytes used while rechunking. Half of the budget
+    /// holds the block of the array being copied, and the other half the
+    /// source chunks being decoded, or new chunks being encoded, at the same
+    /// time, which lowers the concurrency of the arrays during the rechunk. A
+    /// block always holds at least one chunk of the new chunk shape and at
+    /// least one chunk is read or written at a time, so those set the smallest
+    /// memory use whatever the budget.
+    pub fn with_max_mem(mut self, max_mem: usize) -> Self {
+        self.max_mem = max_mem;
+        self
+    }
+
+    /// Encode the new array with different codecs than the source array
+    pub fn with_codecs(mut self, codecs: Vec<Extension>) -> Self {
+        self.codecs = Some(codecs);
+        self
+    }
+
+    /// Estimate the size in bytes of a single element for data types without
+    /// a fixed size, such as variable length strings, when sizing the blocks.
+    /// Rechunking those arrays fails without an estimate, and it should cover
+    /// the largest values expected. Data types with a fixed size always use
+    /// their own size.
+    pub fn with_element_size(mut self, element_size: usize) -> Self {
+        self.element_size = Some(element_size);
+        self
+    }
+}
+
+/// The shape of the blocks copied by a rechunk. Blocks are a whole number of
+/// chunks in every dimension, so each chunk is written once without reading
+/// it, and grow from the last dimension while they fit in the memory budget.
+/// Where the smallest block still fits, dimensions are aligned to the least
+/// common multiple of the source and new chunk shapes as well, so each source
+/// chunk is also read only once.
+pub(crate) fn block_shape(
+    shape: &[usize],
+    src_chunk_shape: &[usize],
+    chunk_shape: &[usize],
+    element_size: usize,
+    max_mem: usize,
+) -> Vec<usize> {
+    let mut unit = chunk_shape.to_vec();
+    for axis in (0..shape.len()).rev() {
+        let whole = shape[axis].div_ceil(chunk_shape[axis]).max(1) * chunk_shape[axis];
+        let aligned = lcm(src_chunk_shape[axis], chunk_shape[axis]).min(whole);
+        let others = unit
+            .iter()
+            .enumerate()
+            .filter(|(a, _)| *a != axis)
+            .map(|(_, len)| len)
+            .product::<usize>();
+        if others.saturating_mul(aligned).saturating_mul(element_size) <= max_mem {
+            unit[axis] = aligned;
+        }
+    }
+
+    let mut block = unit.clone();
+    for axis in (0..shape.len()).rev() {
+        let units = shape[axis].div_ceil(unit[axis]).max(1);
+        let others = block
+            .iter()
+            .enumerate()
+            .filter(|(a, _)| *a != axis)
+            .map(|(_, len)| len)
+            .product::<usize>()
+            * element_size;
+        let fit = max_mem / (others * unit[axis]).max(1);
+        block[axis] = unit[axis] * fit.clamp(1, units);
+        if fit < units {
+            break;
+        }
+    }
+    block
+}
+
+fn lcm(a: usize, b: usize) -> usize {
+    let (mut x, mut y) = (a, b);
+    while y != 0 {
+        (x, y) = (y, x % y);
+    }
+    (a / x.max(1)).saturating_mul(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::block_shape;
+
+    #[test]
+    fn test_block_shape() {
+        // Everything fits in one block
+        assert_eq!(block_shape(&[10, 10], &[3, 4], &[3, 4], 8, 1 << 20), vec![12, 12]);
+
+        // The last dimension is filled first, then whole rows of chunks
+        assert_eq!(block_shape(&[100, 100], &[10, 10], &[10, 10], 1, 1000), vec![10, 100]);
+        assert_eq!(block_shape(&[100, 100], &[10, 10], &[10, 10], 1, 2500), vec![20, 100]);
+        assert_eq!(block_shape(&[100, 100], &[10, 10], &[10, 10], 1, 500), vec![10, 50]);
+
+        // A single chunk is the smallest block
+        assert_eq!(block_shape(&[100, 100], &[10, 10], &[10, 10], 4, 16), vec![10, 10]);
+    }
+
+    #[test]
+    fn test_block_shape_aligned() {
+        // Blocks line up with the source chunks as well as the new chunks
+        assert_eq!(block_shape(&[100, 100], &[4, 100], &[10, 10], 1, 2000), vec![20, 100]);
+        assert_eq!(block_shape(&[100, 100], &[6, 100], &[4, 10], 1, 1200), vec![12, 100]);
+
+        // The alignment never goes past the end of the array
+        assert_eq!(block_shape(&[12, 12], &[7, 5], &[3, 4], 1, 144), vec![12, 12]);
+
+        // Dimensions are aligned from the last while the smallest block fits,
+        // the rest only line up with the new chunks
+        assert_eq!(block_shape(&[100, 100], &[3, 7], &[10, 10], 1, 700), vec![10, 70]);
+        assert_eq!(block_shape(&[100, 100], &[3, 7], &[10, 10], 1, 50), vec![10, 10]);
+    }
+}
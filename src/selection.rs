@@ -0,0 +1,565 @@
+use std::ops::Range;
+
+use ndarray::{ArrayViewD, Dimension, SliceInfo, SliceInfoElem};
+
+use crate::{error::CharizarrError, index::DimSelection};
+
+/// A single element of a basic selection, following numpy basic indexing.
+/// Negative indices count from the end of the dimension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionElem {
+    /// A range of the dimension with a positive step, `end` is exclusive and
+    /// None selects to the end of the dimension
+    Slice {
+        start: isize,
+        end: Option<isize>,
+        step: isize,
+    },
+    /// A single index, which drops the dimension from the result
+    Index(isize),
+    /// A new axis of length one in the result
+    NewAxis,
+    /// All of the dimensions not covered by the other elements
+    Ellipsis,
+}
+
+impl SelectionElem {
+    /// Select the entire dimension
+    pub fn all() -> Self {
+        SelectionElem::Slice {
+            start: 0,
+            end: None,
+            step: 1,
+        }
+    }
+}
+
+impl From<Range<usize>> for SelectionElem {
+    fn from(range: Range<usize>) -> Self {
+        SelectionElem::Slice {
+            start: range.start as isize,
+            end: Some(range.end as isize),
+            step: 1,
+        }
+    }
+}
+
+impl From<SliceInfoElem> for SelectionElem {
+    fn from(elem: SliceInfoElem) -> Self {
+        match elem {
+            SliceInfoElem::Slice { start, end, step } => SelectionElem::Slice { start, end, step },
+            SliceInfoElem::Index(index) => SelectionElem::Index(index),
+            SliceInfoElem::NewAxis => SelectionElem::NewAxis,
+        }
+    }
+}
+
+/// A selection of an array made from slices and integer indices, like the
+/// `s![]` macro from ndarray. Dimensions that are not selected are selected
+/// entirely, so the default selection is the whole array.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BasicSelection {
+    pub elems: Vec<SelectionElem>,
+}
+
+/// A basic selection resolved against the shape of an array
+#[derive(Debug, Clone)]
+pub(crate) struct ResolvedSelection {
+    /// The selection of every dimension of the array, indexed dimensions are
+    /// selected with a length of one
+    pub dims: Vec<DimSelection>,
+    /// The shape of the result, without indexed dimensions and with new axes
+    pub shape: Vec<usize>,
+}
+
+impl BasicSelection {
+    pub fn new(elems: Vec<SelectionElem>) -> Self {
+        Self { elems }
+    }
+
+    pub(crate) fn resolve(&self, shape: &[usize]) -> Result<ResolvedSelection, CharizarrError> {
+        let is_dim =
+            |e: &&SelectionElem| !matches!(e, SelectionElem::NewAxis | SelectionElem::Ellipsis);
+        let ellipsis_count = self
+            .elems
+            .iter()
+            .filter(|e| matches!(e, SelectionElem::Ellipsis))
+            .count();
+        if ellipsis_count > 1 {
+            return Err(CharizarrError::ArrayError(
+                "A selection can only contain one ellipsis".to_string(),
+            ));
+        }
+
+        let ndim = self.elems.iter().filter(is_dim).count();
+        if ndim > shape.len() {
+            return Err(CharizarrError::ArrayError(format!(
+                "Selection has {ndim} dimensions but the array has {}",
+                shape.len()
+            )));
+        }
+
+        // Expand the ellipsis, or pad the end of the selection, to cover
+        // every dimension
+        let fill = vec![SelectionElem::all(); shape.len() - ndim];
+        let mut elems = Vec::with_capacity(self.elems.len() + fill.len());
+        match self
+            .elems
+            .iter()
+            .position(|e| matches!(e, SelectionElem::Ellipsis))
+        {
+            Some(pos) => {
+                elems.extend_from_slice(&self.elems[..pos]);
+                elems.extend(fill);
+                elems.extend_from_slice(&self.elems[pos + 1..]);
+            }
+            None => {
+                elems.extend_from_slice(&self.elems);
+                elems.extend(fill);
+            }
+        }
+
+        let mut dims = Vec::with_capacity(shape.len());
+        let mut out_shape = Vec::with_capacity(elems.len());
+        let mut dim_lens = shape.iter().enumerate();
+        for elem in elems {
+            if elem == SelectionElem::NewAxis {
+                out_shape.push(1);
+                continue;
+            }
+
+            let (axis, &len) = dim_lens.next().expect("Selection rank was checked");
+            match elem {
+                SelectionElem::Slice { start, end, step } => {
+                    if step < 1 {
+                        return Err(CharizarrError::ArrayError(format!(
+                            "Step must be positive, got {step} for axis {axis}"
+                        )));
+                    }
+                    let start = resolve_bound(start, len, axis)?;
+                    let end = end.map_or(Ok(len), |end| resolve_bound(end, len, axis))?;
+                    let dim = DimSelection::new(start..end.max(start), step as usize);
+                    out_shape.push(dim.nitems());
+                    dims.push(dim);
+                }
+                SelectionElem::Index(index) => {
+                    let index = resolve_index(index, len, axis)?;
+                    dims.push(DimSelection::new(index..index + 1, 1));
+                }
+                SelectionElem::NewAxis | SelectionElem::Ellipsis => unreachable!(),
+            }
+        }
+
+        Ok(ResolvedSelection {
+            dims,
+            shape: out_shape,
+        })
+    }
+}
+
+/// Resolve a possibly negative bound of a dimension, which must lie within the
+/// dimension
+fn resolve_bound(bound: isize, len: usize, axis: usize) -> Result<usize, CharizarrError> {
+    let resolved = if bound < 0 {
+        len as isize + bound
+    } else {
+        bound
+    };
+    if resolved < 0 || resolved as usize > len {
+        return Err(CharizarrError::ArrayError(format!(
+            "{bound} is out of bounds for axis {axis} with length {len}"
+        )));
+    }
+    Ok(resolved as usize)
+}
+
+impl From<Vec<SelectionElem>> for BasicSelection {
+    fn from(elems: Vec<SelectionElem>) -> Self {
+        Self::new(elems)
+    }
+}
+
+impl From<Vec<Range<usize>>> for BasicSelection {
+    fn from(ranges: Vec<Range<usize>>) -> Self {
+        Self::new(ranges.into_iter().map(SelectionElem::from).collect())
+    }
+}
+
+impl From<&[Range<usize>]> for BasicSelection {
+    fn from(ranges: &[Range<usize>]) -> Self {
+        Self::new(ranges.iter().cloned().map(SelectionElem::from).collect())
+    }
+}
+
+impl<T, Din, Dout> From<SliceInfo<T, Din, Dout>> for BasicSelection
+where
+    T: AsRef<[SliceInfoElem]>,
+    Din: Dimension,
+    Dout: Dimension,
+{
+    fn from(info: SliceInfo<T, Din, Dout>) -> Self {
+        Self::from(&info)
+    }
+}
+
+impl<T, Din, Dout> From<&SliceInfo<T, Din, Dout>> for BasicSelection
+where
+    T: AsRef<[SliceInfoElem]>,
+    Din: Dimension,
+    Dout: Dimension,
+{
+    fn from(info: &SliceInfo<T, Din, Dout>) -> Self {
+        let elems: &[SliceInfoElem] = info.as_ref();
+        Self::new(elems.iter().copied().map(SelectionElem::from).collect())
+    }
+}
+
+/// A selection of a single dimension of an orthogonal selection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrthogonalElem {
+    /// A range of the dimension with a positive step, like
+    /// `SelectionElem::Slice`
+    Slice {
+        start: isize,
+        end: Option<isize>,
+        step: isize,
+    },
+    /// A single index, which drops the dimension from the result
+    Index(isize),
+    /// A list of indices in any order, which may repeat
+    Indices(Vec<isize>),
+    /// A mask with the length of the dimension, selecting the indices that
+    /// are true
+    Mask(Vec<bool>),
+}
+
+impl OrthogonalElem {
+    /// Select the entire dimension
+    pub fn all() -> Self {
+        OrthogonalElem::Slice {
+            start: 0,
+            end: None,
+            step: 1,
+        }
+    }
+}
+
+impl From<Range<usize>> for OrthogonalElem {
+    fn from(range: Range<usize>) -> Self {
+        OrthogonalElem::Slice {
+            start: range.start as isize,
+            end: Some(range.end as isize),
+            step: 1,
+        }
+    }
+}
+
+impl From<Vec<usize>> for OrthogonalElem {
+    fn from(indices: Vec<usize>) -> Self {
+        OrthogonalElem::Indices(indices.into_iter().map(|i| i as isize).collect())
+    }
+}
+
+impl From<Vec<isize>> for OrthogonalElem {
+    fn from(indices: Vec<isize>) -> Self {
+        OrthogonalElem::Indices(indices)
+    }
+}
+
+impl From<Vec<bool>> for OrthogonalElem {
+    fn from(mask: Vec<bool>) -> Self {
+        OrthogonalElem::Mask(mask)
+    }
+}
+
+/// An orthogonal, or outer, selection of an array, where each dimension is
+/// selected independently by a slice, an index, a list of indices or a
+/// boolean mask. The result holds every combination of the selected indices,
+/// like `numpy.ix_`. Dimensions that are not selected are selected entirely.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OrthogonalSelection {
+    pub elems: Vec<OrthogonalElem>,
+}
+
+/// An orthogonal selection resolved against the shape of an array
+#[derive(Debug, Clone)]
+pub(crate) struct ResolvedOrthogonalSelection {
+    /// The indices selected from every dimension of the array, in the order
+    /// of the result
+    pub dims: Vec<Vec<usize>>,
+    /// The shape of the result, without indexed dimensions
+    pub shape: Vec<usize>,
+}
+
+impl OrthogonalSelection {
+    pub fn new(elems: Vec<OrthogonalElem>) -> Self {
+        Self { elems }
+    }
+
+    pub(crate) fn resolve(
+        &self,
+        shape: &[usize],
+    ) -> Result<ResolvedOrthogonalSelection, CharizarrError> {
+        if self.elems.len() > shape.len() {
+            return Err(CharizarrError::ArrayError(format!(
+                "Selection has {} dimensions but the array has {}",
+                self.elems.len(),
+                shape.len()
+            )));
+        }
+
+        let all = OrthogonalElem::all();
+        let elems = self
+            .elems
+            .iter()
+            .chain(std::iter::repeat(&all))
+            .take(shape.len());
+
+        let mut dims = Vec::with_capacity(shape.len());
+        let mut out_shape = Vec::with_capacity(shape.len());
+        for (axis, (elem, &len)) in elems.zip(shape).enumerate() {
+            let indices = match elem {
+                OrthogonalElem::Slice { start, end, step } => {
+                    let elem = SelectionElem::Slice {
+                        start: *start,
+                        end: *end,
+                        step: *step,
+                    };
+                    let resolved = BasicSelection::new(vec![elem]).resolve(&[len])?;
+                    let dim = &resolved.dims[0];
+                    dim.range.clone().step_by(dim.step).collect()
+                }
+                OrthogonalElem::Index(index) => {
+                    let resolved =
+                        BasicSelection::new(vec![SelectionElem::Index(*index)]).resolve(&[len])?;
+                    dims.push(vec![resolved.dims[0].range.start]);
+                    continue;
+                }
+                OrthogonalElem::Indices(indices) => indices
+                    .iter()
+                    .map(|&index| resolve_index(index, len, axis))
+                    .collect::<Result<Vec<_>, _>>()?,
+                OrthogonalElem::Mask(mask) => {
+                    if mask.len() != len {
+                        return Err(CharizarrError::ArrayError(format!(
+                            "Mask has length {} but axis {axis} has length {len}",
+                            mask.len()
+                        )));
+                    }
+                    mask.iter()
+                        .enumerate()
+                        .filter_map(|(i, selected)| selected.then_some(i))
+                        .collect()
+                }
+            };
+            out_shape.push(indices.len());
+            dims.push(indices);
+        }
+
+        Ok(ResolvedOrthogonalSelection {
+            dims,
+            shape: out_shape,
+        })
+    }
+}
+
+/// Resolve a possibly negative index, which must be an item of the dimension
+fn resolve_index(index: isize, len: usize, axis: usize) -> Result<usize, CharizarrError> {
+    resolve_bound(index, len, axis)
+        .ok()
+        .filter(|i| *i < len)
+        .ok_or_else(|| {
+            CharizarrError::ArrayError(format!(
+                "Index {index} is out of bounds for axis {axis} with length {len}"
+            ))
+        })
+}
+
+impl From<Vec<OrthogonalElem>> for OrthogonalSelection {
+    fn from(elems: Vec<OrthogonalElem>) -> Self {
+        Self::new(elems)
+    }
+}
+
+impl From<Vec<Vec<usize>>> for OrthogonalSelection {
+    fn from(indices: Vec<Vec<usize>>) -> Self {
+        Self::new(indices.into_iter().map(OrthogonalElem::from).collect())
+    }
+}
+
+/// A selection of individual points of an array, like `vindex` in
+/// zarr-python. The points are given as one list of coordinates per
+/// dimension, which must all have the same length, and the result is a one
+/// dimensional array of the values at each point in order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CoordinateSelection {
+    pub coords: Vec<Vec<isize>>,
+}
+
+impl CoordinateSelection {
+    pub fn new(coords: Vec<Vec<isize>>) -> Self {
+        Self { coords }
+    }
+
+    /// Create a selection from a list of points, each holding a coordinate
+    /// for every dimension
+    pub fn from_points(points: &[Vec<usize>]) -> Result<Self, CharizarrError> {
+        let ndim = points.first().map_or(0, |point| point.len());
+        if let Some(point) = points.iter().find(|point| point.len() != ndim) {
+            return Err(CharizarrError::ArrayError(format!(
+                "Point {point:?} has {} dimensions but the first point has {ndim}",
+                point.len()
+            )));
+        }
+
+        let coords = (0..ndim)
+            .map(|dim| points.iter().map(|point| point[dim] as isize).collect())
+            .collect();
+        Ok(Self { coords })
+    }
+
+    /// Create a selection of the points where a mask is true, in row major
+    /// order
+    pub fn from_mask(mask: ArrayViewD<bool>) -> Self {
+        let mut coords = vec![vec![]; mask.ndim()];
+        for (index, _) in mask.indexed_iter().filter(|(_, selected)| **selected) {
+            for (dim_coords, i) in coords.iter_mut().zip(index.slice()) {
+                dim_coords.push(*i as isize);
+            }
+        }
+        Self { coords }
+    }
+
+    pub(crate) fn resolve(&self, shape: &[usize]) -> Result<Vec<Vec<usize>>, CharizarrError> {
+        if self.coords.len() != shape.len() {
+            return Err(CharizarrError::ArrayError(format!(
+                "Selection has {} dimensions but the array has {}",
+                self.coords.len(),
+                shape.len()
+            )));
+        }
+
+        let npoints = self.coords.first().map_or(0, |coords| coords.len());
+        if let Some(axis) = self.coords.iter().position(|c| c.len() != npoints) {
+            return Err(CharizarrError::ArrayError(format!(
+                "Axis {axis} has {} coordinates but axis 0 has {npoints}",
+                self.coords[axis].len()
+            )));
+        }
+
+        self.coords
+            .iter()
+            .zip(shape)
+            .enumerate()
+            .map(|(axis, (coords, &len))| {
+                coords
+                    .iter()
+                    .map(|&index| resolve_index(index, len, axis))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl From<Vec<Vec<isize>>> for CoordinateSelection {
+    fn from(coords: Vec<Vec<isize>>) -> Self {
+        Self::new(coords)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::s;
+
+    use super::*;
+
+    #[test]
+    fn test_resolve_basic_selection() {
+        let selection = BasicSelection::from(s![1..;2, -1, ..-2]);
+        let resolved = selection.resolve(&[10, 4, 6]).unwrap();
+        assert_eq!(
+            resolved.dims,
+            vec![
+                DimSelection::new(1..10, 2),
+                DimSelection::new(3..4, 1),
+                DimSelection::new(0..4, 1)
+            ]
+        );
+        assert_eq!(resolved.shape, vec![5, 4]);
+
+        let selection = BasicSelection::new(vec![
+            SelectionElem::Ellipsis,
+            SelectionElem::NewAxis,
+            SelectionElem::Index(0),
+        ]);
+        let resolved = selection.resolve(&[10, 4, 6]).unwrap();
+        assert_eq!(resolved.dims[0], DimSelection::new(0..10, 1));
+        assert_eq!(resolved.dims[2], DimSelection::new(0..1, 1));
+        assert_eq!(resolved.shape, vec![10, 4, 1]);
+
+        // Missing trailing dimensions are selected entirely
+        let resolved = BasicSelection::from(s![2..4]).resolve(&[10, 4]).unwrap();
+        assert_eq!(resolved.shape, vec![2, 4]);
+        let resolved = BasicSelection::default().resolve(&[10, 4]).unwrap();
+        assert_eq!(resolved.shape, vec![10, 4]);
+
+        assert!(BasicSelection::from(s![0..11]).resolve(&[10]).is_err());
+        assert!(BasicSelection::from(s![10]).resolve(&[10]).is_err());
+        assert!(BasicSelection::from(s![-11]).resolve(&[10]).is_err());
+        assert!(BasicSelection::from(s![..;-1]).resolve(&[10]).is_err());
+        assert!(BasicSelection::from(s![.., ..]).resolve(&[10]).is_err());
+    }
+
+    #[test]
+    fn test_resolve_orthogonal_selection() {
+        let selection = OrthogonalSelection::new(vec![
+            vec![3usize, 0, 3].into(),
+            vec![true, false, true, true].into(),
+        ]);
+        let resolved = selection.resolve(&[5, 4, 2]).unwrap();
+        assert_eq!(
+            resolved.dims,
+            vec![vec![3, 0, 3], vec![0, 2, 3], vec![0, 1]]
+        );
+        assert_eq!(resolved.shape, vec![3, 3, 2]);
+
+        let selection = OrthogonalSelection::new(vec![
+            OrthogonalElem::Index(-1),
+            OrthogonalElem::Slice {
+                start: 0,
+                end: None,
+                step: 2,
+            },
+        ]);
+        let resolved = selection.resolve(&[5, 4]).unwrap();
+        assert_eq!(resolved.dims, vec![vec![4], vec![0, 2]]);
+        assert_eq!(resolved.shape, vec![2]);
+
+        let out_of_bounds = OrthogonalSelection::new(vec![vec![5usize].into()]);
+        assert!(out_of_bounds.resolve(&[5]).is_err());
+        let short_mask = OrthogonalSelection::new(vec![vec![true].into()]);
+        assert!(short_mask.resolve(&[5]).is_err());
+    }
+
+    #[test]
+    fn test_resolve_coordinate_selection() {
+        let selection = CoordinateSelection::new(vec![vec![0, -1], vec![2, 2]]);
+        let resolved = selection.resolve(&[5, 4]).unwrap();
+        assert_eq!(resolved, vec![vec![0, 4], vec![2, 2]]);
+
+        let selection = CoordinateSelection::from_points(&[vec![0, 2], vec![4, 2]]).unwrap();
+        assert_eq!(selection.resolve(&[5, 4]).unwrap(), resolved);
+        assert!(CoordinateSelection::from_points(&[vec![0, 2], vec![4]]).is_err());
+
+        let ragged = CoordinateSelection::new(vec![vec![0, 1], vec![2]]);
+        assert!(ragged.resolve(&[5, 4]).is_err());
+        let out_of_bounds = CoordinateSelection::new(vec![vec![0], vec![4]]);
+        assert!(out_of_bounds.resolve(&[5, 4]).is_err());
+        let short = CoordinateSelection::from_points(&[vec![0]]).unwrap();
+        assert!(short.resolve(&[5, 4]).is_err());
+
+        let mask = ndarray::array![[false, true], [true, false], [false, true]].into_dyn();
+        let selection = CoordinateSelection::from_mask(mask.view());
+        assert_eq!(selection.coords, vec![vec![0, 1, 2], vec![1, 0, 1]]);
+    }
+}
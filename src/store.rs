@@ -7,9 +7,20 @@ pub type KeyRangeValues = (String, Range<usize>, Vec<u8>);
 
 /// Read only store interface
 pub trait ReadableStore {
-    /// Retrieve the value associated with a given key
+    /// Retrieve the value associated with a given key, erroring with
+    /// `CharizarrError::KeyNotFoundError` if the key does not exist
     async fn get(&self, key: &str) -> Result<Vec<u8>, CharizarrError>;
 
+    /// Retrieve the size in bytes of the value associated with a given key,
+    /// erroring with `CharizarrError::KeyNotFoundError` if the key does not
+    /// exist
+    ///
+    /// By default this reads the whole value, stores should override it when
+    /// the size can be found without reading the value.
+    async fn get_size(&self, key: &str) -> Result<usize, CharizarrError> {
+        self.get(key).await.map(|value| value.len())
+    }
+
     /// Retrieve possibly partial values from given key_ranges.
     ///
     /// The key_ranges are a list of (key, range) tuples, where range is a
@@ -68,4 +79,43 @@ pub trait WriteableStore {
 
     /// Erase all keys with the given prefix from the store:
     async fn erase_prefix(&self, prefix: &str) -> Result<(), CharizarrError>;
+
+    /// Move the value of a key to another key, replacing any existing value.
+    ///
+    /// By default this is not implemented, and it is optional for stores to
+    /// implement. Callers fall back to copying the value and erasing the
+    /// original key.
+    async fn rename(&self, _from: &str, _to: &str) -> Result<(), CharizarrError> {
+        Err(CharizarrError::UnimplementedError("rename"))
+    }
+}
+
+/// Erase every key under a node prefix, or only list them for a dry run.
+/// Returns the keys that were, or would be, erased.
+pub(crate) async fn erase_node<T>(store: &T, prefix: &str, dry_run: bool) -> Result<Vec<String>, CharizarrError>
+where
+    T: ListableStore + WriteableStore,
+{
+    let mut keys = store.list_prefix(prefix).await?;
+    keys.sort();
+    if !dry_run && !keys.is_empty() {
+        store.erase_prefix(prefix).await?;
+    }
+    Ok(keys)
+}
+
+/// Move the value of a key to another key, using the native rename of the
+/// store when it has one
+pub(crate) async fn move_key<T>(store: &T, from: &str, to: &str) -> Result<(), CharizarrError>
+where
+    T: ReadableStore + WriteableStore,
+{
+    match store.rename(from, to).await {
+        Err(CharizarrError::UnimplementedError(_)) => {
+            let value = store.get(from).await?;
+            store.set(to, &value).await?;
+            store.erase(from).await
+        }
+        result => result,
+    }
 }
@@ -1,4 +1,4 @@
-use futures::{StreamExt, TryFutureExt};
+use futures::{stream, StreamExt, TryFutureExt, TryStreamExt};
 use object_store::{path::Path, ObjectMeta, ObjectStore, PutPayload};
 
 use crate::{
@@ -6,6 +6,8 @@ use crate::{
     store::{ListableStore, ReadableStore, WriteableStore},
 };
 
+/// A store backed by an `object_store` implementation, with every key under a
+/// root path. Listed keys are relative to the root, like the keys passed in.
 pub struct ZarrObjectStore {
     store: Box<dyn ObjectStore>,
     root: Path,
@@ -18,19 +20,21 @@ impl ZarrObjectStore {
 
     fn path_for_key(&self, key: &str) -> Path {
         key.split("/")
-            .into_iter()
+            .filter(|part| !part.is_empty())
             .fold(self.root.clone(), |path, part| path.child(part))
     }
 
+    /// The store key for an object path, relative to the store root
+    fn key_for_path(&self, path: &Path) -> String {
+        path.prefix_match(&self.root)
+            .map(|parts| parts.map(|part| part.as_ref().to_string()).collect::<Vec<_>>().join("/"))
+            .unwrap_or_else(|| path.to_string())
+    }
+
     async fn list_meta(&self, prefix: Option<&str>) -> Result<Vec<ObjectMeta>, CharizarrError> {
-        let path = if let Some(prefix) = prefix {
-            let child = self.path_for_key(prefix);
-            Some(child)
-        } else {
-            None
-        };
+        let path = self.path_for_key(prefix.unwrap_or(""));
         self.store
-            .list(path.as_ref())
+            .list(Some(&path))
             .collect::<Vec<_>>()
             .await
             .into_iter()
@@ -47,7 +51,12 @@ impl ReadableStore for ZarrObjectStore {
         let result = self
             .store
             .get(&path)
-            .map_err(|e| CharizarrError::StoreError(format!("Failed to read object: {e}")))
+            .map_err(|e| match e {
+                object_store::Error::NotFound { .. } => {
+                    CharizarrError::KeyNotFoundError(key.to_string())
+                }
+                e => CharizarrError::StoreError(format!("Failed to read object: {e}")),
+            })
             .await?
             .bytes()
             .await
@@ -58,6 +67,22 @@ impl ReadableStore for ZarrObjectStore {
         let data = result.to_vec();
         Ok(data)
     }
+
+    async fn get_size(&self, key: &str) -> Result<usize, CharizarrError> {
+        let path = self.path_for_key(key);
+        let meta = self
+            .store
+            .head(&path)
+            .map_err(|e| match e {
+                object_store::Error::NotFound { .. } => {
+                    CharizarrError::KeyNotFoundError(key.to_string())
+                }
+                e => CharizarrError::StoreError(format!("Failed to read object metadata: {e}")),
+            })
+            .await?;
+
+        Ok(meta.size)
+    }
 }
 
 impl ListableStore for ZarrObjectStore {
@@ -66,7 +91,7 @@ impl ListableStore for ZarrObjectStore {
             .list_meta(None)
             .await?
             .into_iter()
-            .map(|meta| meta.location.to_string())
+            .map(|meta| self.key_for_path(&meta.location))
             .collect();
 
         Ok(meta)
@@ -77,7 +102,7 @@ impl ListableStore for ZarrObjectStore {
             .list_meta(Some(prefix))
             .await?
             .into_iter()
-            .map(|meta| meta.location.to_string())
+            .map(|meta| self.key_for_path(&meta.location))
             .collect();
 
         Ok(meta)
@@ -106,7 +131,12 @@ impl WriteableStore for ZarrObjectStore {
         let path = self.path_for_key(key);
         self.store
             .delete(&path)
-            .map_err(|e| CharizarrError::StoreError(format!("Failed to delete object: {e}")))
+            .map_err(|e| match e {
+                object_store::Error::NotFound { .. } => {
+                    CharizarrError::KeyNotFoundError(key.to_string())
+                }
+                e => CharizarrError::StoreError(format!("Failed to delete object: {e}")),
+            })
             .await?;
 
         Ok(())
@@ -121,6 +151,29 @@ impl WriteableStore for ZarrObjectStore {
     }
 
     async fn erase_prefix(&self, prefix: &str) -> Result<(), CharizarrError> {
-        self.erase(prefix).await
+        let locations = self
+            .list_meta(Some(prefix))
+            .await?
+            .into_iter()
+            .map(|meta| Ok(meta.location));
+        self.store
+            .delete_stream(stream::iter(locations).boxed())
+            .try_collect::<Vec<_>>()
+            .await
+            .map_err(|e| CharizarrError::StoreError(format!("Failed to delete objects: {e}")))?;
+
+        Ok(())
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<(), CharizarrError> {
+        self.store
+            .rename(&self.path_for_key(from), &self.path_for_key(to))
+            .map_err(|e| match e {
+                object_store::Error::NotFound { .. } => {
+                    CharizarrError::KeyNotFoundError(from.to_string())
+                }
+                e => CharizarrError::StoreError(format!("Failed to rename object: {e}")),
+            })
+            .await
     }
 }
@@ -0,0 +1,323 @@
+use std::collections::HashSet;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    codec::ByteToArrayCodec,
+    codecs::bytes::BytesCodec,
+    data_type::CoreDataType,
+    error::CharizarrError,
+    metadata::{DataType, Extension},
+    zarray::ZArray,
+};
+
+pub const STRUCTURED_NAME: &str = "structured";
+
+/// A single named field of a structured record
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StructField {
+    pub name: String,
+    pub data_type: CoreDataType,
+    pub offset: usize,
+}
+
+impl StructField {
+    pub fn size(&self) -> usize {
+        self.data_type
+            .size()
+            .expect("Structured fields always have a fixed size")
+    }
+
+    /// The width of each byte swapped unit of the field, complex values are
+    /// swapped per component
+    fn swap_width(&self) -> usize {
+        match self.data_type {
+            CoreDataType::Complex64 | CoreDataType::Complex128 => self.size() / 2,
+            _ => self.size(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum FieldConfig {
+    Packed(String, CoreDataType),
+    Offset {
+        name: String,
+        data_type: CoreDataType,
+        offset: usize,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+struct StructuredConfig {
+    fields: Vec<FieldConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    itemsize: Option<usize>,
+}
+
+/// A structured data type, equivalent to a numpy structured dtype. Each record
+/// is `itemsize` bytes long and holds its fields at the given byte offsets.
+///
+/// Fields are written as `[name, data_type]` pairs when they are packed, or as
+/// objects with an explicit offset along with the itemsize when they are not.
+///
+/// Adapted from https://github.com/zarr-developers/zarr-extensions/tree/main/data-types/structured
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StructuredDataType {
+    pub(crate) fields: Vec<StructField>,
+    pub(crate) itemsize: usize,
+}
+
+impl StructuredDataType {
+    /// Create a structured data type with the fields packed in order
+    pub fn new(fields: Vec<(String, CoreDataType)>) -> Result<Self, CharizarrError> {
+        let mut offset = 0;
+        let mut packed = Vec::with_capacity(fields.len());
+        for (name, data_type) in fields {
+            let size = data_type.size().unwrap_or(0);
+            packed.push(StructField {
+                name,
+                data_type,
+                offset,
+            });
+            offset += size;
+        }
+        Self::with_offsets(packed, None)
+    }
+
+    /// Create a structured data type with explicit field offsets. The itemsize
+    /// defaults to the end of the last field.
+    pub fn with_offsets(
+        fields: Vec<StructField>,
+        itemsize: Option<usize>,
+    ) -> Result<Self, CharizarrError> {
+        if fields.is_empty() {
+            return Err(CharizarrError::TypeError(
+                "Structured data types must have at least one field".to_string(),
+            ));
+        }
+
+        let mut names = HashSet::new();
+        for field in fields.iter() {
+            if field.data_type.size().is_none() {
+                return Err(CharizarrError::TypeError(format!(
+                    "Field {} must have a fixed size data type",
+                    field.name
+                )));
+            }
+            if !names.insert(field.name.as_str()) {
+                return Err(CharizarrError::TypeError(format!(
+                    "Duplicate field {}",
+                    field.name
+                )));
+            }
+        }
+
+        let end = fields
+            .iter()
+            .map(|f| f.offset + f.size())
+            .max()
+            .unwrap_or(0);
+        let itemsize = itemsize.unwrap_or(end);
+        if itemsize < end {
+            return Err(CharizarrError::TypeError(format!(
+                "Fields extend past the itemsize of {itemsize}"
+            )));
+        }
+
+        Ok(Self { fields, itemsize })
+    }
+
+    /// Parse the structured data type from an extension data type, returning
+    /// None if it is not a structured data type
+    pub fn from_data_type(data_type: &DataType) -> Result<Option<Self>, CharizarrError> {
+        let DataType::Extension(ext) = data_type else {
+            return Ok(None);
+        };
+        if ext.name != STRUCTURED_NAME {
+            return Ok(None);
+        }
+
+        let config = serde_json::from_value::<StructuredConfig>(ext.configuration.clone())
+            .map_err(|e| CharizarrError::TypeError(format!("{}: {e}", ext.name)))?;
+        let mut offset = 0;
+        let fields = config
+            .fields
+            .into_iter()
+            .map(|field| {
+                let field = match field {
+                    FieldConfig::Packed(name, data_type) => StructField {
+                        name,
+                        data_type,
+                        offset,
+                    },
+                    FieldConfig::Offset {
+                        name,
+                        data_type,
+                        offset,
+                    } => StructField {
+                        name,
+                        data_type,
+                        offset,
+                    },
+                };
+                offset = field.offset + field.data_type.size().unwrap_or(0);
+                field
+            })
+            .collect();
+
+        Self::with_offsets(fields, config.itemsize).map(Some)
+    }
+
+    pub fn to_data_type(&self) -> DataType {
+        let packed = Self::new(
+            self.fields
+                .iter()
+                .map(|f| (f.name.clone(), f.data_type.clone()))
+                .collect(),
+        )
+        .is_ok_and(|p| &p == self);
+
+        let fields = self
+            .fields
+            .iter()
+            .map(|f| match packed {
+                true => FieldConfig::Packed(f.name.clone(), f.data_type.clone()),
+                false => FieldConfig::Offset {
+                    name: f.name.clone(),
+                    data_type: f.data_type.clone(),
+                    offset: f.offset,
+                },
+            })
+            .collect();
+        let config = StructuredConfig {
+            fields,
+            itemsize: (!packed).then_some(self.itemsize),
+        };
+
+        DataType::Extension(Extension {
+            name: STRUCTURED_NAME.to_string(),
+            configuration: serde_json::to_value(config)
+                .expect("Structured config is always valid json"),
+        })
+    }
+
+    /// The fields of each record, in the order they were declared
+    pub fn fields(&self) -> &[StructField] {
+        &self.fields
+    }
+
+    /// The size of each record in bytes
+    pub fn itemsize(&self) -> usize {
+        self.itemsize
+    }
+
+    pub fn field(&self, name: &str) -> Option<&StructField> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+
+    /// Parse a fill value into the little endian bytes of a single record. The
+    /// fill value is either the base64 encoded record, as written by
+    /// zarr-python, or the value of each field as an array or object.
+    pub fn fill_value(&self, value: &Value) -> Option<Vec<u8>> {
+        let field_values = match value {
+            Value::String(s) => {
+                return STANDARD.decode(s).ok().filter(|b| b.len() == self.itemsize)
+            }
+            Value::Array(values) if values.len() == self.fields.len() => {
+                values.iter().collect::<Vec<_>>()
+            }
+            Value::Object(values) => self
+                .fields
+                .iter()
+                .map(|f| values.get(&f.name))
+                .collect::<Option<Vec<_>>>()?,
+            _ => return None,
+        };
+
+        let mut record = vec![0; self.itemsize];
+        for (field, value) in self.fields.iter().zip(field_values) {
+            let data_type = DataType::Core(field.data_type.clone());
+            let value = ZArray::full(&data_type, &[1], value).ok()?;
+            let bytes = encode_le(&data_type, &value).ok()?;
+            record[field.offset..field.offset + field.size()].copy_from_slice(&bytes);
+        }
+        Some(record)
+    }
+
+    /// Swap the byte order of each field of a record in place
+    pub(crate) fn swap_fields(&self, record: &mut [u8]) {
+        for field in self.fields.iter() {
+            record[field.offset..field.offset + field.size()]
+                .chunks_mut(field.swap_width())
+                .for_each(|b| b.reverse());
+        }
+    }
+}
+
+/// Encode a core array as packed little endian bytes
+pub(crate) fn encode_le(data_type: &DataType, arr: &ZArray) -> Result<Vec<u8>, CharizarrError> {
+    BytesCodec::new().encode(data_type, &serde_json::json!({"endian": "little"}), arr)
+}
+
+/// Decode packed little endian bytes into a core array
+pub(crate) fn decode_le(data_type: &DataType, bytes: &[u8]) -> Result<ZArray, CharizarrError> {
+    BytesCodec::new().decode(data_type, &serde_json::json!({"endian": "little"}), bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_structured_data_type() {
+        let data_type: DataType = serde_json::from_value(json!({
+            "name": "structured",
+            "configuration": {"fields": [["lat", "float64"], ["lon", "float64"], ["qc", "uint8"]]}
+        }))
+        .unwrap();
+        let structured = StructuredDataType::from_data_type(&data_type)
+            .unwrap()
+            .unwrap();
+        assert_eq!(structured.itemsize(), 17);
+        assert_eq!(structured.field("qc").unwrap().offset, 16);
+        assert_eq!(structured.to_data_type(), data_type);
+        assert_eq!(data_type.size(), Some(17));
+
+        let data_type: DataType = serde_json::from_value(json!({
+            "name": "structured",
+            "configuration": {
+                "fields": [
+                    {"name": "qc", "data_type": "uint8", "offset": 0},
+                    {"name": "lat", "data_type": "float32", "offset": 4}
+                ],
+                "itemsize": 8
+            }
+        }))
+        .unwrap();
+        let structured = StructuredDataType::from_data_type(&data_type)
+            .unwrap()
+            .unwrap();
+        assert_eq!(structured.itemsize(), 8);
+        assert_eq!(structured.to_data_type(), data_type);
+
+        let fill = structured
+            .fill_value(&json!({"qc": 1, "lat": 1.0}))
+            .unwrap();
+        assert_eq!(fill, vec![1, 0, 0, 0, 0, 0, 0x80, 0x3f]);
+        assert_eq!(structured.fill_value(&json!("AQAAAAAAgD8=")), Some(fill));
+        assert_eq!(structured.fill_value(&json!([1])), None);
+
+        assert!(StructuredDataType::new(vec![
+            ("a".to_string(), CoreDataType::Int8),
+            ("a".to_string(), CoreDataType::Int8),
+        ])
+        .is_err());
+        assert!(StructuredDataType::new(vec![("a".to_string(), CoreDataType::String)]).is_err());
+    }
+}
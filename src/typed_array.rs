@@ -0,0 +1,219 @@
+use std::{marker::PhantomData, ops::Range};
+
+use futures::{Stream, StreamExt};
+use ndarray::{ArrayD, ArrayViewD, ArrayViewMutD};
+
+use crate::{
+    array::{Array, ChunkOrder},
+    codec_registry::CodecRegistry,
+    element::Element,
+    error::CharizarrError,
+    index::ChunkProjection,
+    selection::{BasicSelection, CoordinateSelection, OrthogonalSelection},
+    store::{ListableStore, ReadableStore, WriteableStore},
+    zarray::ZArray,
+};
+
+/// An array with a data type known at compile time. The data type is checked
+/// once when the typed array is created, so reads return `ArrayD<E>` directly.
+pub struct TypedArray<'a, S, E>
+where
+    S: ReadableStore + ListableStore + WriteableStore,
+    E: Element,
+{
+    array: Array<'a, S>,
+    element: PhantomData<E>,
+}
+
+impl<'a, S, E> TypedArray<'a, S, E>
+where
+    S: ReadableStore + ListableStore + WriteableStore,
+    E: Element,
+{
+    /// Wrap an array, erroring if its data type is not held by the element type
+    pub fn new(array: Array<'a, S>) -> Result<Self, CharizarrError> {
+        let data_type = array.array_data_type()?;
+        if !E::matches(&data_type) {
+            return Err(CharizarrError::TypeError(format!(
+                "{:?}, the array has data type {}",
+                E::data_type(),
+                data_type
+            )));
+        }
+
+        Ok(Self {
+            array,
+            element: PhantomData,
+        })
+    }
+
+    /// Open an existing array from a store, checking its data type
+    pub async fn open(
+        store: &'a S,
+        path: Option<String>,
+        codec_registry: Option<CodecRegistry>,
+    ) -> Result<Self, CharizarrError> {
+        Self::new(Array::open(store, path, codec_registry).await?)
+    }
+
+    /// The untyped array
+    pub fn array(&self) -> &Array<'a, S> {
+        &self.array
+    }
+
+    pub fn into_inner(self) -> Array<'a, S> {
+        self.array
+    }
+
+    /// Read a chunk from the store
+    pub async fn get_chunk(&self, id: &[usize]) -> Result<ArrayD<E>, CharizarrError> {
+        E::from_zarray(self.array.get_chunk(id).await?)
+    }
+
+    /// Write a chunk to the store
+    pub async fn set_chunk(
+        &self,
+        id: &[usize],
+        chunk: ArrayViewD<'_, E>,
+    ) -> Result<(), CharizarrError> {
+        self.array
+            .set_chunk(id, &E::into_zarray(chunk.to_owned()))
+            .await
+    }
+
+    /// Read the given index ranges of the array, or the entire array
+    pub async fn get(&self, index: Option<Vec<Range<usize>>>) -> Result<ArrayD<E>, CharizarrError> {
+        E::from_zarray(self.array.get(index).await?)
+    }
+
+    /// Write the values to the given index ranges of the array, or the entire
+    /// array
+    pub async fn set(
+        &self,
+        index: Option<Vec<Range<usize>>>,
+        value: ArrayViewD<'_, E>,
+    ) -> Result<(), CharizarrError> {
+        self.array
+            .set(index, &E::into_zarray(value.to_owned()))
+            .await
+    }
+
+    /// Read a selection of slices and integer indices from the array
+    pub async fn get_basic_selection(
+        &self,
+        selection: impl Into<BasicSelection>,
+    ) -> Result<ArrayD<E>, CharizarrError> {
+        E::from_zarray(self.array.get_basic_selection(selection).await?)
+    }
+
+    /// Write the values to a selection of slices and integer indices of the
+    /// array
+    pub async fn set_basic_selection(
+        &self,
+        selection: impl Into<BasicSelection>,
+        value: ArrayViewD<'_, E>,
+    ) -> Result<(), CharizarrError> {
+        self.array
+            .set_basic_selection(selection, &E::into_zarray(value.to_owned()))
+            .await
+    }
+
+    /// Read a selection of slices and integer indices into a buffer with the
+    /// shape of the selection
+    pub async fn get_into(
+        &self,
+        selection: impl Into<BasicSelection>,
+        out: &mut ArrayViewMutD<'_, E>,
+    ) -> Result<(), CharizarrError> {
+        self.array.get_into(selection, out).await
+    }
+
+    /// Read an orthogonal selection of indices, masks and slices from the
+    /// array
+    pub async fn get_orthogonal_selection(
+        &self,
+        selection: impl Into<OrthogonalSelection>,
+    ) -> Result<ArrayD<E>, CharizarrError> {
+        E::from_zarray(self.array.get_orthogonal_selection(selection).await?)
+    }
+
+    /// Write the values to an orthogonal selection of the array
+    pub async fn set_orthogonal_selection(
+        &self,
+        selection: impl Into<OrthogonalSelection>,
+        value: ArrayViewD<'_, E>,
+    ) -> Result<(), CharizarrError> {
+        self.array
+            .set_orthogonal_selection(selection, &E::into_zarray(value.to_owned()))
+            .await
+    }
+
+    /// Read the values at individual points of the array
+    pub async fn get_coordinate_selection(
+        &self,
+        selection: impl Into<CoordinateSelection>,
+    ) -> Result<ArrayD<E>, CharizarrError> {
+        E::from_zarray(self.array.get_coordinate_selection(selection).await?)
+    }
+
+    /// Write the values to individual points of the array
+    pub async fn set_coordinate_selection(
+        &self,
+        selection: impl Into<CoordinateSelection>,
+        value: ArrayViewD<'_, E>,
+    ) -> Result<(), CharizarrError> {
+        self.array
+            .set_coordinate_selection(selection, &E::into_zarray(value.to_owned()))
+            .await
+    }
+
+    /// Read the values where a mask of the same shape as the array is true
+    pub async fn get_mask_selection(
+        &self,
+        mask: ArrayViewD<'_, bool>,
+    ) -> Result<ArrayD<E>, CharizarrError> {
+        let mask = ZArray::Bool(mask.to_owned());
+        E::from_zarray(self.array.get_mask_selection(&mask).await?)
+    }
+
+    /// Write the values to where a mask of the same shape as the array is
+    /// true
+    pub async fn set_mask_selection(
+        &self,
+        mask: ArrayViewD<'_, bool>,
+        value: ArrayViewD<'_, E>,
+    ) -> Result<(), CharizarrError> {
+        let mask = ZArray::Bool(mask.to_owned());
+        self.array
+            .set_mask_selection(&mask, &E::into_zarray(value.to_owned()))
+            .await
+    }
+
+    /// Change the shape of the array, see `Array::resize`
+    pub async fn resize(&mut self, new_shape: Vec<usize>) -> Result<(), CharizarrError> {
+        self.array.resize(new_shape).await
+    }
+
+    /// Grow the array along an axis and write the values to the new region
+    pub async fn append(&mut self, axis: usize, value: ArrayViewD<'_, E>) -> Result<(), CharizarrError> {
+        self.array.append(axis, &E::into_zarray(value.to_owned())).await
+    }
+
+    /// Stream the chunks holding a selection of the array, see
+    /// `Array::iter_chunks`
+    pub fn iter_chunks(
+        &self,
+        selection: impl Into<BasicSelection>,
+        prefetch: usize,
+        order: ChunkOrder,
+    ) -> Result<
+        impl Stream<Item = Result<(ChunkProjection, ArrayD<E>), CharizarrError>> + '_,
+        CharizarrError,
+    > {
+        let chunks = self.array.iter_chunks(selection, prefetch, order)?;
+        Ok(chunks.map(|chunk| {
+            let (projection, data) = chunk?;
+            Ok((projection, E::from_zarray(data)?))
+        }))
+    }
+}
@@ -1,8 +1,17 @@
+use chrono::{NaiveDateTime, TimeDelta};
+use half::{bf16, f16};
 use ndarray::prelude::*;
-use num::Complex;
+use num::{traits::AsPrimitive, Complex};
+
+use serde_json::Value;
 
 use crate::{
-    data_type::CoreDataType, error::CharizarrError, index::ChunkProjection, metadata::DataType,
+    data_type::CoreDataType,
+    datetime::{TimeConfig, TimeDataType, TimeKind},
+    error::CharizarrError,
+    fill_value::FillValue,
+    structured::{decode_le, encode_le, StructuredDataType},
+    index::Projection, metadata::DataType,
 };
 
 #[derive(Debug, Clone, PartialEq)]
@@ -16,16 +25,67 @@ pub enum ZArray {
     UInt16(ArrayD<u16>),
     UInt32(ArrayD<u32>),
     UInt64(ArrayD<u64>),
+    Float16(ArrayD<f16>),
+    BFloat16(ArrayD<bf16>),
     Float32(ArrayD<f32>),
     Float64(ArrayD<f64>),
     Complex64(ArrayD<Complex<f32>>),
     Complex128(ArrayD<Complex<f64>>),
     Raw8(ArrayD<u8>),
     Raw16(ArrayD<u16>),
+    String(ArrayD<String>),
+    Bytes(ArrayD<Vec<u8>>),
+    DateTime64(ArrayD<i64>, TimeConfig),
+    TimeDelta64(ArrayD<i64>, TimeConfig),
+    /// Records of a structured data type, each stored as its little endian bytes
+    Structured(ArrayD<Vec<u8>>, StructuredDataType),
+}
+
+macro_rules! cast_array {
+    ($arr:expr, $dtype:expr) => {
+        match $dtype {
+            DataType::Core(CoreDataType::Int8) => Ok(ZArray::Int8(cast_elements($arr))),
+            DataType::Core(CoreDataType::Int16) => Ok(ZArray::Int16(cast_elements($arr))),
+            DataType::Core(CoreDataType::Int32) => Ok(ZArray::Int32(cast_elements($arr))),
+            DataType::Core(CoreDataType::Int64) => Ok(ZArray::Int64(cast_elements($arr))),
+            DataType::Core(CoreDataType::UInt8) => Ok(ZArray::UInt8(cast_elements($arr))),
+            DataType::Core(CoreDataType::UInt16) => Ok(ZArray::UInt16(cast_elements($arr))),
+            DataType::Core(CoreDataType::UInt32) => Ok(ZArray::UInt32(cast_elements($arr))),
+            DataType::Core(CoreDataType::UInt64) => Ok(ZArray::UInt64(cast_elements($arr))),
+            DataType::Core(CoreDataType::Float16) => Ok(ZArray::Float16(cast_elements($arr))),
+            DataType::Core(CoreDataType::BFloat16) => Ok(ZArray::BFloat16(cast_elements($arr))),
+            DataType::Core(CoreDataType::Float32) => Ok(ZArray::Float32(cast_elements($arr))),
+            DataType::Core(CoreDataType::Float64) => Ok(ZArray::Float64(cast_elements($arr))),
+            _ => Err(CharizarrError::TypeError($dtype.to_string())),
+        }
+    };
+}
+
+macro_rules! set_selection {
+    ($arr:expr, $sel:expr, $value:expr, $d_type:ty) => {{
+        let target_chunk: ArrayViewD<$d_type> = $value.try_into()?;
+        $sel.assign($arr, target_chunk);
+    }};
+}
+
+fn cast_elements<S, T>(arr: &ArrayD<S>) -> ArrayD<T>
+where
+    S: AsPrimitive<T>,
+    T: Copy + 'static,
+{
+    arr.mapv(|v| v.as_())
 }
 
 impl ZArray {
     pub fn zeros(dtype: &DataType, shape: &[usize]) -> Result<Self, CharizarrError> {
+        if let Some(time) = TimeDataType::from_data_type(dtype)? {
+            return Ok(ZArray::from_ticks(time, ArrayD::zeros(IxDyn(shape))));
+        }
+        if let Some(structured) = StructuredDataType::from_data_type(dtype)? {
+            let record = vec![0; structured.itemsize];
+            return Ok(ZArray::Structured(ArrayD::from_elem(IxDyn(shape), record), structured));
+        }
+
         let DataType::Core(dtype) = dtype else {
             return Err(CharizarrError::TypeError(dtype.to_string()));
         };
@@ -40,12 +100,65 @@ impl ZArray {
             CoreDataType::UInt16 => ZArray::UInt16(ArrayD::zeros(IxDyn(shape))),
             CoreDataType::UInt32 => ZArray::UInt32(ArrayD::zeros(IxDyn(shape))),
             CoreDataType::UInt64 => ZArray::UInt64(ArrayD::zeros(IxDyn(shape))),
+            CoreDataType::Float16 => ZArray::Float16(ArrayD::zeros(IxDyn(shape))),
+            CoreDataType::BFloat16 => ZArray::BFloat16(ArrayD::zeros(IxDyn(shape))),
             CoreDataType::Float32 => ZArray::Float32(ArrayD::zeros(IxDyn(shape))),
             CoreDataType::Float64 => ZArray::Float64(ArrayD::zeros(IxDyn(shape))),
             CoreDataType::Complex64 => ZArray::Complex64(ArrayD::zeros(IxDyn(shape))),
             CoreDataType::Complex128 => ZArray::Complex128(ArrayD::zeros(IxDyn(shape))),
             CoreDataType::Raw8 => ZArray::Raw8(ArrayD::zeros(IxDyn(shape))),
             CoreDataType::Raw16 => ZArray::Raw16(ArrayD::zeros(IxDyn(shape))),
+            CoreDataType::String => ZArray::String(ArrayD::default(IxDyn(shape))),
+            CoreDataType::Bytes => ZArray::Bytes(ArrayD::default(IxDyn(shape))),
+        };
+
+        Ok(zarray)
+    }
+
+    /// Create an array filled with the given fill value, as stored in the
+    /// array metadata
+    pub fn full(dtype: &DataType, shape: &[usize], fill_value: &Value) -> Result<Self, CharizarrError> {
+        if let Some(time) = TimeDataType::from_data_type(dtype)? {
+            let ticks = time.fill_value(fill_value).ok_or_else(|| {
+                CharizarrError::ArrayError(format!(
+                    "Invalid fill value {fill_value} for data type {dtype}"
+                ))
+            })?;
+            return Ok(ZArray::from_ticks(time, ArrayD::from_elem(IxDyn(shape), ticks)));
+        }
+        if let Some(structured) = StructuredDataType::from_data_type(dtype)? {
+            let record = structured.fill_value(fill_value).ok_or_else(|| {
+                CharizarrError::ArrayError(format!(
+                    "Invalid fill value {fill_value} for data type {dtype}"
+                ))
+            })?;
+            return Ok(ZArray::Structured(ArrayD::from_elem(IxDyn(shape), record), structured));
+        }
+
+        let DataType::Core(core) = dtype else {
+            return Err(CharizarrError::TypeError(dtype.to_string()));
+        };
+
+        let zarray = match core {
+            CoreDataType::Bool => ZArray::Bool(full_array(shape, fill_value, dtype)?),
+            CoreDataType::Int8 => ZArray::Int8(full_array(shape, fill_value, dtype)?),
+            CoreDataType::Int16 => ZArray::Int16(full_array(shape, fill_value, dtype)?),
+            CoreDataType::Int32 => ZArray::Int32(full_array(shape, fill_value, dtype)?),
+            CoreDataType::Int64 => ZArray::Int64(full_array(shape, fill_value, dtype)?),
+            CoreDataType::UInt8 => ZArray::UInt8(full_array(shape, fill_value, dtype)?),
+            CoreDataType::UInt16 => ZArray::UInt16(full_array(shape, fill_value, dtype)?),
+            CoreDataType::UInt32 => ZArray::UInt32(full_array(shape, fill_value, dtype)?),
+            CoreDataType::UInt64 => ZArray::UInt64(full_array(shape, fill_value, dtype)?),
+            CoreDataType::Float16 => ZArray::Float16(full_array(shape, fill_value, dtype)?),
+            CoreDataType::BFloat16 => ZArray::BFloat16(full_array(shape, fill_value, dtype)?),
+            CoreDataType::Float32 => ZArray::Float32(full_array(shape, fill_value, dtype)?),
+            CoreDataType::Float64 => ZArray::Float64(full_array(shape, fill_value, dtype)?),
+            CoreDataType::Complex64 => ZArray::Complex64(full_array(shape, fill_value, dtype)?),
+            CoreDataType::Complex128 => ZArray::Complex128(full_array(shape, fill_value, dtype)?),
+            CoreDataType::Raw8 => ZArray::Raw8(full_array(shape, fill_value, dtype)?),
+            CoreDataType::Raw16 => ZArray::Raw16(full_array(shape, fill_value, dtype)?),
+            CoreDataType::String => ZArray::String(full_array(shape, fill_value, dtype)?),
+            CoreDataType::Bytes => ZArray::Bytes(full_array(shape, fill_value, dtype)?),
         };
 
         Ok(zarray)
@@ -62,115 +175,278 @@ impl ZArray {
             ZArray::UInt16(arr) => ZArray::UInt16(arr.into_shape(shape).unwrap()),
             ZArray::UInt32(arr) => ZArray::UInt32(arr.into_shape(shape).unwrap()),
             ZArray::UInt64(arr) => ZArray::UInt64(arr.into_shape(shape).unwrap()),
+            ZArray::Float16(arr) => ZArray::Float16(arr.into_shape(shape).unwrap()),
+            ZArray::BFloat16(arr) => ZArray::BFloat16(arr.into_shape(shape).unwrap()),
             ZArray::Float32(arr) => ZArray::Float32(arr.into_shape(shape).unwrap()),
             ZArray::Float64(arr) => ZArray::Float64(arr.into_shape(shape).unwrap()),
             ZArray::Complex64(arr) => ZArray::Complex64(arr.into_shape(shape).unwrap()),
             ZArray::Complex128(arr) => ZArray::Complex128(arr.into_shape(shape).unwrap()),
             ZArray::Raw8(arr) => ZArray::Raw8(arr.into_shape(shape).unwrap()),
             ZArray::Raw16(arr) => ZArray::Raw16(arr.into_shape(shape).unwrap()),
+            ZArray::String(arr) => ZArray::String(arr.into_shape(shape).unwrap()),
+            ZArray::Bytes(arr) => ZArray::Bytes(arr.into_shape(shape).unwrap()),
+            ZArray::DateTime64(arr, config) => ZArray::DateTime64(arr.into_shape(shape).unwrap(), config),
+            ZArray::TimeDelta64(arr, config) => ZArray::TimeDelta64(arr.into_shape(shape).unwrap(), config),
+            ZArray::Structured(arr, structured) => ZArray::Structured(arr.into_shape(shape).unwrap(), structured),
         }
     }
 
-    /// Set the value of a chunk at a given selection.
-    /// TODO: MAKE THIS WAY CLEANER
-    pub fn set(&mut self, sel: &ChunkProjection, value: &Self) -> Result<(), CharizarrError> {
+    /// The shape of the underlying array
+    pub fn shape(&self) -> &[usize] {
         match self {
-            ZArray::Bool(arr) => {
-                let target_chunk: ArrayViewD<bool> = value.try_into()?;
-                let target = target_chunk.slice_each_axis(|a| sel.chunk_sel[a.axis.0]);
-                let mut arr_view = arr.slice_each_axis_mut(|a| sel.out_sel[a.axis.0]);
-                arr_view.assign(&target);
-            }
-            ZArray::Int8(arr) => {
-                let target_chunk: ArrayViewD<i8> = value.try_into()?;
-                let target = target_chunk.slice_each_axis(|a| sel.chunk_sel[a.axis.0]);
-                let mut arr_view = arr.slice_each_axis_mut(|a| sel.out_sel[a.axis.0]);
-                arr_view.assign(&target);
-            }
-            ZArray::Int16(arr) => {
-                let target_chunk: ArrayViewD<i16> = value.try_into()?;
-                let target = target_chunk.slice_each_axis(|a| sel.chunk_sel[a.axis.0]);
-                let mut arr_view = arr.slice_each_axis_mut(|a| sel.out_sel[a.axis.0]);
-                arr_view.assign(&target);
-            }
-            ZArray::Int32(arr) => {
-                let target_chunk: ArrayViewD<i32> = value.try_into()?;
-                let target = target_chunk.slice_each_axis(|a| sel.chunk_sel[a.axis.0]);
-                let mut arr_view = arr.slice_each_axis_mut(|a| sel.out_sel[a.axis.0]);
-                arr_view.assign(&target);
-            }
-            ZArray::Int64(arr) => {
-                let target_chunk: ArrayViewD<i64> = value.try_into()?;
-                let target = target_chunk.slice_each_axis(|a| sel.chunk_sel[a.axis.0]);
-                let mut arr_view = arr.slice_each_axis_mut(|a| sel.out_sel[a.axis.0]);
-                arr_view.assign(&target);
-            }
-            ZArray::UInt8(arr) => {
-                let target_chunk: ArrayViewD<u8> = value.try_into()?;
-                let target = target_chunk.slice_each_axis(|a| sel.chunk_sel[a.axis.0]);
-                let mut arr_view = arr.slice_each_axis_mut(|a| sel.out_sel[a.axis.0]);
-                arr_view.assign(&target);
-            }
-            ZArray::UInt16(arr) => {
-                let target_chunk: ArrayViewD<u16> = value.try_into()?;
-                let target = target_chunk.slice_each_axis(|a| sel.chunk_sel[a.axis.0]);
-                let mut arr_view = arr.slice_each_axis_mut(|a| sel.out_sel[a.axis.0]);
-                arr_view.assign(&target);
-            }
-            ZArray::UInt32(arr) => {
-                let target_chunk: ArrayViewD<u32> = value.try_into()?;
-                let target = target_chunk.slice_each_axis(|a| sel.chunk_sel[a.axis.0]);
-                let mut arr_view = arr.slice_each_axis_mut(|a| sel.out_sel[a.axis.0]);
-                arr_view.assign(&target);
-            }
-            ZArray::UInt64(arr) => {
-                let target_chunk: ArrayViewD<u64> = value.try_into()?;
-                let target = target_chunk.slice_each_axis(|a| sel.chunk_sel[a.axis.0]);
-                let mut arr_view = arr.slice_each_axis_mut(|a| sel.out_sel[a.axis.0]);
-                arr_view.assign(&target);
-            }
-            ZArray::Float32(arr) => {
-                let target_chunk: ArrayViewD<f32> = value.try_into()?;
-                let target = target_chunk.slice_each_axis(|a| sel.chunk_sel[a.axis.0]);
-                let mut arr_view = arr.slice_each_axis_mut(|a| sel.out_sel[a.axis.0]);
-                arr_view.assign(&target);
-            }
-            ZArray::Float64(arr) => {
-                let target_chunk: ArrayViewD<f64> = value.try_into()?;
-                let target = target_chunk.slice_each_axis(|a| sel.chunk_sel[a.axis.0]);
-                let mut arr_view = arr.slice_each_axis_mut(|a| sel.out_sel[a.axis.0]);
-                arr_view.assign(&target);
-            }
-            ZArray::Complex64(arr) => {
-                let target_chunk: ArrayViewD<Complex<f32>> = value.try_into()?;
-                let target = target_chunk.slice_each_axis(|a| sel.chunk_sel[a.axis.0]);
-                let mut arr_view = arr.slice_each_axis_mut(|a| sel.out_sel[a.axis.0]);
-                arr_view.assign(&target);
+            ZArray::Bool(arr) => arr.shape(),
+            ZArray::Int8(arr) => arr.shape(),
+            ZArray::Int16(arr) => arr.shape(),
+            ZArray::Int32(arr) => arr.shape(),
+            ZArray::Int64(arr) => arr.shape(),
+            ZArray::UInt8(arr) => arr.shape(),
+            ZArray::UInt16(arr) => arr.shape(),
+            ZArray::UInt32(arr) => arr.shape(),
+            ZArray::UInt64(arr) => arr.shape(),
+            ZArray::Float16(arr) => arr.shape(),
+            ZArray::BFloat16(arr) => arr.shape(),
+            ZArray::Float32(arr) => arr.shape(),
+            ZArray::Float64(arr) => arr.shape(),
+            ZArray::Complex64(arr) => arr.shape(),
+            ZArray::Complex128(arr) => arr.shape(),
+            ZArray::Raw8(arr) => arr.shape(),
+            ZArray::Raw16(arr) => arr.shape(),
+            ZArray::String(arr) => arr.shape(),
+            ZArray::Bytes(arr) => arr.shape(),
+            ZArray::DateTime64(arr, _) | ZArray::TimeDelta64(arr, _) => arr.shape(),
+            ZArray::Structured(arr, _) => arr.shape(),
+        }
+    }
+
+    /// The zarr data type of the underlying array
+    pub fn data_type(&self) -> DataType {
+        let dtype = match self {
+            ZArray::Bool(_) => CoreDataType::Bool,
+            ZArray::Int8(_) => CoreDataType::Int8,
+            ZArray::Int16(_) => CoreDataType::Int16,
+            ZArray::Int32(_) => CoreDataType::Int32,
+            ZArray::Int64(_) => CoreDataType::Int64,
+            ZArray::UInt8(_) => CoreDataType::UInt8,
+            ZArray::UInt16(_) => CoreDataType::UInt16,
+            ZArray::UInt32(_) => CoreDataType::UInt32,
+            ZArray::UInt64(_) => CoreDataType::UInt64,
+            ZArray::Float16(_) => CoreDataType::Float16,
+            ZArray::BFloat16(_) => CoreDataType::BFloat16,
+            ZArray::Float32(_) => CoreDataType::Float32,
+            ZArray::Float64(_) => CoreDataType::Float64,
+            ZArray::Complex64(_) => CoreDataType::Complex64,
+            ZArray::Complex128(_) => CoreDataType::Complex128,
+            ZArray::Raw8(_) => CoreDataType::Raw8,
+            ZArray::Raw16(_) => CoreDataType::Raw16,
+            ZArray::String(_) => CoreDataType::String,
+            ZArray::Bytes(_) => CoreDataType::Bytes,
+            ZArray::DateTime64(_, config) => {
+                return TimeDataType::new(TimeKind::DateTime64, *config).to_data_type()
             }
-            ZArray::Complex128(arr) => {
-                let target_chunk: ArrayViewD<Complex<f64>> = value.try_into()?;
-                let target = target_chunk.slice_each_axis(|a| sel.chunk_sel[a.axis.0]);
-                let mut arr_view = arr.slice_each_axis_mut(|a| sel.out_sel[a.axis.0]);
-                arr_view.assign(&target);
+            ZArray::TimeDelta64(_, config) => {
+                return TimeDataType::new(TimeKind::TimeDelta64, *config).to_data_type()
             }
-            ZArray::Raw8(arr) => {
-                let target_chunk: ArrayViewD<u8> = value.try_into()?;
-                let target = target_chunk.slice_each_axis(|a| sel.chunk_sel[a.axis.0]);
-                let mut arr_view = arr.slice_each_axis_mut(|a| sel.out_sel[a.axis.0]);
-                arr_view.assign(&target);
+            ZArray::Structured(_, structured) => return structured.to_data_type(),
+        };
+        DataType::Core(dtype)
+    }
+
+    /// Cast the array to another numeric data type, with the same semantics
+    /// as an `as` cast of each element
+    pub fn astype(&self, dtype: &DataType) -> Result<Self, CharizarrError> {
+        if &self.data_type() == dtype {
+            return Ok(self.clone());
+        }
+
+        match self {
+            ZArray::Int8(arr) => cast_array!(arr, dtype),
+            ZArray::Int16(arr) => cast_array!(arr, dtype),
+            ZArray::Int32(arr) => cast_array!(arr, dtype),
+            ZArray::Int64(arr) => cast_array!(arr, dtype),
+            ZArray::UInt8(arr) => cast_array!(arr, dtype),
+            ZArray::UInt16(arr) => cast_array!(arr, dtype),
+            ZArray::UInt32(arr) => cast_array!(arr, dtype),
+            ZArray::UInt64(arr) => cast_array!(arr, dtype),
+            ZArray::Float16(arr) => cast_array!(arr, dtype),
+            ZArray::BFloat16(arr) => cast_array!(arr, dtype),
+            ZArray::Float32(arr) => cast_array!(arr, dtype),
+            ZArray::Float64(arr) => cast_array!(arr, dtype),
+            _ => Err(CharizarrError::TypeError(dtype.to_string())),
+        }
+    }
+
+    /// Set the value of a chunk at a given selection.
+    pub fn set<P: Projection>(&mut self, sel: &P, value: &Self) -> Result<(), CharizarrError> {
+        match self {
+            ZArray::Bool(arr) => set_selection!(arr, sel, value, bool),
+            ZArray::Int8(arr) => set_selection!(arr, sel, value, i8),
+            ZArray::Int16(arr) => set_selection!(arr, sel, value, i16),
+            ZArray::Int32(arr) => set_selection!(arr, sel, value, i32),
+            ZArray::Int64(arr) => set_selection!(arr, sel, value, i64),
+            ZArray::UInt8(arr) => set_selection!(arr, sel, value, u8),
+            ZArray::UInt16(arr) => set_selection!(arr, sel, value, u16),
+            ZArray::UInt32(arr) => set_selection!(arr, sel, value, u32),
+            ZArray::UInt64(arr) => set_selection!(arr, sel, value, u64),
+            ZArray::Float16(arr) => set_selection!(arr, sel, value, f16),
+            ZArray::BFloat16(arr) => set_selection!(arr, sel, value, bf16),
+            ZArray::Float32(arr) => set_selection!(arr, sel, value, f32),
+            ZArray::Float64(arr) => set_selection!(arr, sel, value, f64),
+            ZArray::Complex64(arr) => set_selection!(arr, sel, value, Complex<f32>),
+            ZArray::Complex128(arr) => set_selection!(arr, sel, value, Complex<f64>),
+            ZArray::Raw8(arr) => set_selection!(arr, sel, value, u8),
+            ZArray::Raw16(arr) => set_selection!(arr, sel, value, u16),
+            ZArray::String(arr) => set_selection!(arr, sel, value, String),
+            ZArray::Bytes(arr) => set_selection!(arr, sel, value, Vec<u8>),
+            ZArray::DateTime64(arr, config) => match value {
+                ZArray::DateTime64(v, v_config) if v_config == config => {
+                    sel.assign(arr, v.view())
+                }
+                _ => return Err(CharizarrError::TypeError(value.data_type().to_string())),
+            },
+            ZArray::TimeDelta64(arr, config) => match value {
+                ZArray::TimeDelta64(v, v_config) if v_config == config => {
+                    sel.assign(arr, v.view())
+                }
+                _ => return Err(CharizarrError::TypeError(value.data_type().to_string())),
+            },
+            ZArray::Structured(arr, structured) => match value {
+                ZArray::Structured(v, v_structured) if v_structured == structured => {
+                    sel.assign(arr, v.view())
+                }
+                _ => return Err(CharizarrError::TypeError(value.data_type().to_string())),
+            },
+        }
+
+        Ok(())
+    }
+}
+
+impl ZArray {
+    /// Wrap raw i64 ticks as a datetime64 or timedelta64 array
+    pub fn from_ticks(time: TimeDataType, ticks: ArrayD<i64>) -> Self {
+        match time.kind {
+            TimeKind::DateTime64 => ZArray::DateTime64(ticks, time.config),
+            TimeKind::TimeDelta64 => ZArray::TimeDelta64(ticks, time.config),
+        }
+    }
+
+    /// The raw i64 ticks of a datetime64 or timedelta64 array
+    pub fn ticks(&self) -> Result<&ArrayD<i64>, CharizarrError> {
+        match self {
+            ZArray::DateTime64(arr, _) | ZArray::TimeDelta64(arr, _) => Ok(arr),
+            other => Err(CharizarrError::TypeError(other.data_type().to_string())),
+        }
+    }
+
+    /// Convert a datetime64 array to chrono datetimes, with NaT as None
+    pub fn to_datetimes(&self) -> Result<ArrayD<Option<NaiveDateTime>>, CharizarrError> {
+        let ZArray::DateTime64(arr, config) = self else {
+            return Err(CharizarrError::TypeError(self.data_type().to_string()));
+        };
+        Ok(arr.mapv(|t| config.to_datetime(t)))
+    }
+
+    /// Create a datetime64 array from chrono datetimes, with None as NaT
+    pub fn from_datetimes(
+        datetimes: &ArrayD<Option<NaiveDateTime>>,
+        config: TimeConfig,
+    ) -> Result<Self, CharizarrError> {
+        let ticks = datetimes
+            .iter()
+            .map(|d| config.from_datetime(*d))
+            .collect::<Result<Vec<i64>, _>>()?;
+        let ticks = ArrayD::from_shape_vec(datetimes.raw_dim(), ticks)
+            .expect("Conversion preserves the number of elements");
+        Ok(ZArray::DateTime64(ticks, config))
+    }
+
+    /// Convert a timedelta64 array to chrono durations, with NaT as None
+    pub fn to_timedeltas(&self) -> Result<ArrayD<Option<TimeDelta>>, CharizarrError> {
+        let ZArray::TimeDelta64(arr, config) = self else {
+            return Err(CharizarrError::TypeError(self.data_type().to_string()));
+        };
+        Ok(arr.mapv(|t| config.to_timedelta(t)))
+    }
+
+    /// Create a timedelta64 array from chrono durations, with None as NaT
+    pub fn from_timedeltas(
+        timedeltas: &ArrayD<Option<TimeDelta>>,
+        config: TimeConfig,
+    ) -> Result<Self, CharizarrError> {
+        let ticks = timedeltas
+            .iter()
+            .map(|d| config.from_timedelta(*d))
+            .collect::<Result<Vec<i64>, _>>()?;
+        let ticks = ArrayD::from_shape_vec(timedeltas.raw_dim(), ticks)
+            .expect("Conversion preserves the number of elements");
+        Ok(ZArray::TimeDelta64(ticks, config))
+    }
+
+    /// Read a single field of a structured array as its own array
+    pub fn field(&self, name: &str) -> Result<Self, CharizarrError> {
+        let ZArray::Structured(arr, structured) = self else {
+            return Err(CharizarrError::TypeError(self.data_type().to_string()));
+        };
+        let field = structured
+            .field(name)
+            .ok_or_else(|| CharizarrError::ArrayError(format!("No field named {name}")))?;
+
+        let bytes = arr
+            .iter()
+            .flat_map(|record| &record[field.offset..field.offset + field.size()])
+            .copied()
+            .collect::<Vec<u8>>();
+        let values = decode_le(&DataType::Core(field.data_type.clone()), &bytes)?;
+        Ok(values.reshape(arr.shape()))
+    }
+
+    /// Create a structured array from one array per field, in the order of the
+    /// fields of the data type. Padding between fields is zeroed.
+    pub fn from_fields(structured: StructuredDataType, fields: &[ZArray]) -> Result<Self, CharizarrError> {
+        if fields.len() != structured.fields.len() {
+            return Err(CharizarrError::ArrayError(format!(
+                "Expected {} fields, got {}",
+                structured.fields.len(),
+                fields.len()
+            )));
+        }
+        let shape = fields[0].shape().to_vec();
+        let count = shape.iter().product::<usize>();
+
+        let mut records = vec![vec![0; structured.itemsize]; count];
+        for (field, values) in structured.fields.iter().zip(fields) {
+            let data_type = DataType::Core(field.data_type.clone());
+            if values.shape() != shape.as_slice() || values.data_type() != data_type {
+                return Err(CharizarrError::ArrayError(format!(
+                    "Field {} must be a {} array of shape {:?}",
+                    field.name, data_type, shape
+                )));
             }
-            ZArray::Raw16(arr) => {
-                let target_chunk: ArrayViewD<u16> = value.try_into()?;
-                let target = target_chunk.slice_each_axis(|a| sel.chunk_sel[a.axis.0]);
-                let mut arr_view = arr.slice_each_axis_mut(|a| sel.out_sel[a.axis.0]);
-                arr_view.assign(&target);
+            let bytes = encode_le(&data_type, values)?;
+            for (record, value) in records.iter_mut().zip(bytes.chunks(field.size())) {
+                record[field.offset..field.offset + field.size()].copy_from_slice(value);
             }
         }
 
-        Ok(())
+        let arr = ArrayD::from_shape_vec(IxDyn(&shape), records)
+            .expect("One record is created per element");
+        Ok(ZArray::Structured(arr, structured))
     }
 }
 
+fn full_array<T: FillValue + Clone>(
+    shape: &[usize],
+    fill_value: &Value,
+    dtype: &DataType,
+) -> Result<ArrayD<T>, CharizarrError> {
+    let value = T::from_fill_value(fill_value).ok_or_else(|| {
+        CharizarrError::ArrayError(format!(
+            "Invalid fill value {fill_value} for data type {dtype}"
+        ))
+    })?;
+    Ok(ArrayD::from_elem(IxDyn(shape), value))
+}
+
 macro_rules! into_array {
     ($d_name:path, $d_type:ty) => {
         impl TryInto<ArrayD<$d_type>> for ZArray {
@@ -196,10 +472,14 @@ into_array!(ZArray::UInt8, u8);
 into_array!(ZArray::UInt16, u16);
 into_array!(ZArray::UInt32, u32);
 into_array!(ZArray::UInt64, u64);
+into_array!(ZArray::Float16, f16);
+into_array!(ZArray::BFloat16, bf16);
 into_array!(ZArray::Float32, f32);
 into_array!(ZArray::Float64, f64);
 into_array!(ZArray::Complex64, Complex<f32>);
 into_array!(ZArray::Complex128, Complex<f64>);
+into_array!(ZArray::String, String);
+into_array!(ZArray::Bytes, Vec<u8>);
 
 macro_rules! into_array_view {
     ($d_name:path, $d_type:ty) => {
@@ -226,10 +506,14 @@ into_array_view!(ZArray::UInt8, u8);
 into_array_view!(ZArray::UInt16, u16);
 into_array_view!(ZArray::UInt32, u32);
 into_array_view!(ZArray::UInt64, u64);
+into_array_view!(ZArray::Float16, f16);
+into_array_view!(ZArray::BFloat16, bf16);
 into_array_view!(ZArray::Float32, f32);
 into_array_view!(ZArray::Float64, f64);
 into_array_view!(ZArray::Complex64, Complex<f32>);
 into_array_view!(ZArray::Complex128, Complex<f64>);
+into_array_view!(ZArray::String, String);
+into_array_view!(ZArray::Bytes, Vec<u8>);
 
 macro_rules! into_array_view_mut {
     ($d_name:path, $d_type:ty) => {
@@ -256,10 +540,14 @@ into_array_view_mut!(ZArray::UInt8, u8);
 into_array_view_mut!(ZArray::UInt16, u16);
 into_array_view_mut!(ZArray::UInt32, u32);
 into_array_view_mut!(ZArray::UInt64, u64);
+into_array_view_mut!(ZArray::Float16, f16);
+into_array_view_mut!(ZArray::BFloat16, bf16);
 into_array_view_mut!(ZArray::Float32, f32);
 into_array_view_mut!(ZArray::Float64, f64);
 into_array_view_mut!(ZArray::Complex64, Complex<f32>);
 into_array_view_mut!(ZArray::Complex128, Complex<f64>);
+into_array_view_mut!(ZArray::String, String);
+into_array_view_mut!(ZArray::Bytes, Vec<u8>);
 
 macro_rules! into_chunk {
     ($d_name:expr, $d_type:ty) => {
@@ -281,7 +569,11 @@ into_chunk!(ZArray::UInt8, u8);
 into_chunk!(ZArray::UInt16, u16);
 into_chunk!(ZArray::UInt32, u32);
 into_chunk!(ZArray::UInt64, u64);
+into_chunk!(ZArray::Float16, f16);
+into_chunk!(ZArray::BFloat16, bf16);
 into_chunk!(ZArray::Float32, f32);
 into_chunk!(ZArray::Float64, f64);
 into_chunk!(ZArray::Complex64, Complex<f32>);
 into_chunk!(ZArray::Complex128, Complex<f64>);
+into_chunk!(ZArray::String, String);
+into_chunk!(ZArray::Bytes, Vec<u8>);
@@ -1,15 +1,46 @@
 use std::{collections::HashMap, sync::Arc};
 
 use charizarr::{
+    array::ChunkOrder,
+    chunk::CodecExecution,
     codec::Codec,
     codecs::{blosc::BloscCodec, gzip::GZipCodec},
+    data_type_registry::{DataTypeRegistry, ExtensionDataType},
+    datetime::{TimeConfig, TimeDataType, TimeKind, TimeUnit, NAT},
+    error::CharizarrError,
     metadata::{DataType, Extension, ZarrFormat},
+    selection::{CoordinateSelection, OrthogonalElem},
+    store::{ListableStore, ReadableStore, WriteableStore},
+    typed_array::TypedArray,
     zarray::ZArray,
 };
-use ndarray::{Array, ArrayD, IxDyn};
+use chrono::{Days, NaiveDate, TimeDelta};
+use futures::TryStreamExt;
+use ndarray::{array, s, Array, ArrayD, IxDyn};
 use object_store::{local::LocalFileSystem, path::Path};
 use serde_json::Value;
 
+/// Removes a temporary test directory when dropped, so a failing test does
+/// not leave its store behind
+struct TempDir(std::path::PathBuf);
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Create a store in a new temporary directory, which is removed when the
+/// returned guard is dropped
+fn temp_store(name: &str) -> (charizarr::stores::ZarrObjectStore, TempDir) {
+    let dir = std::env::temp_dir().join(format!("charizarr-{}-{name}.zarr", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = Path::from_absolute_path(&dir).expect("Failed to create store in temporary directory");
+    let store = charizarr::stores::ZarrObjectStore::create(Box::new(LocalFileSystem::new()), path);
+    (store, TempDir(dir))
+}
+
 #[tokio::test]
 async fn test_roundtrip() {
     // Create the codec registry
@@ -215,3 +246,1410 @@ async fn test_read() {
         .unwrap();
     assert_eq!(array_data, expected);
 }
+
+#[cfg(feature = "blosc-decoder")]
+#[tokio::test]
+async fn test_read_blosc_decoder() {
+    use charizarr::codecs::blosc_decoder::BloscDecoderCodec;
+
+    // Register the pure rust decoder in place of the blosc library
+    let codecs = Some(
+        charizarr::codec_registry::CodecRegistry::default()
+            .register(Codec::ByteToByte(Arc::new(BloscDecoderCodec::new()))),
+    );
+
+    let local_store = Box::new(LocalFileSystem::new());
+    let path = Path::from_absolute_path(std::env::current_dir().unwrap())
+        .expect("Failed to create store in current directory")
+        .child("tests")
+        .child("data.zarr");
+    let store = charizarr::stores::ZarrObjectStore::create(local_store, path);
+    let group = charizarr::group::Group::open(&store, None).await.unwrap();
+
+    let array = group
+        .get_array("1d.contiguous.blosc.i2", codecs.clone())
+        .await
+        .unwrap();
+    let array_chunk: ArrayD<i16> = array.get_chunk(&[0]).await.unwrap().try_into().unwrap();
+    let expected = Array::from_vec(vec![1i16, 2, 3, 4]).into_dyn();
+    assert_eq!(array_chunk, expected);
+
+    let array = group
+        .get_array("3d.contiguous.i2", codecs.clone())
+        .await
+        .unwrap();
+    let array_data: ArrayD<i16> = array.get(None).await.unwrap().try_into().unwrap();
+    let expected = Array::from_iter(0i16..27)
+        .into_shape(IxDyn(&[3, 3, 3]))
+        .unwrap();
+    assert_eq!(array_data, expected);
+}
+
+#[tokio::test]
+#[allow(clippy::single_range_in_vec_init)]
+async fn test_string_roundtrip() {
+    let (store, _dir) = temp_store("strings");
+
+    let array = charizarr::array::Array::create(
+        &store,
+        Some("stations".into()),
+        None,
+        vec![4],
+        vec![4],
+        None,
+        DataType::Core(charizarr::data_type::CoreDataType::String),
+        serde_json::json!(""),
+        vec![Extension {
+            name: "vlen-utf8".to_string(),
+            configuration: serde_json::json!({}),
+        }],
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let stations = vec!["KBOS".to_string(), "KJFK".to_string(), "".to_string(), "44013".to_string()];
+    let chunk = ZArray::from(stations.clone());
+    array.set_chunk(&[0], &chunk).await.unwrap();
+
+    let data: ArrayD<String> = array.get(None).await.unwrap().try_into().unwrap();
+    assert_eq!(data, Array::from_vec(stations).into_dyn());
+
+    let new_values = ZArray::from(vec!["KPVD".to_string(), "KORD".to_string()]);
+    array.set(Some(vec![0..2]), &new_values).await.unwrap();
+
+    let data: ArrayD<String> = array.get(Some(vec![1..3])).await.unwrap().try_into().unwrap();
+    assert_eq!(data, Array::from_vec(vec!["KORD".to_string(), "".to_string()]).into_dyn());
+}
+
+#[tokio::test]
+async fn test_datetime_roundtrip() {
+    let (store, _dir) = temp_store("datetimes");
+
+    let config = TimeConfig::new(TimeUnit::Second, 1);
+    let array = charizarr::array::Array::create(
+        &store,
+        Some("time".into()),
+        None,
+        vec![4],
+        vec![4],
+        None,
+        TimeDataType::new(TimeKind::DateTime64, config).to_data_type(),
+        serde_json::json!("NaT"),
+        vec![Extension {
+            name: "bytes".to_string(),
+            configuration: serde_json::json!({"endian": "little"}),
+        }],
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let chunk = ZArray::DateTime64(Array::from_vec(vec![0, 86_400, NAT, -60]).into_dyn(), config);
+    array.set_chunk(&[0], &chunk).await.unwrap();
+
+    let data = array.get(None).await.unwrap();
+    let datetimes = data.to_datetimes().unwrap();
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+    assert_eq!(datetimes[[0]], Some(epoch));
+    assert_eq!(datetimes[[1]], epoch.checked_add_days(Days::new(1)));
+    assert_eq!(datetimes[[2]], None);
+    assert_eq!(datetimes[[3]], epoch.checked_sub_signed(TimeDelta::minutes(1)));
+    assert_eq!(ZArray::from_datetimes(&datetimes, config).unwrap(), chunk);
+}
+
+/// Temperatures stored as float32, with a named fill value
+struct Celsius {}
+
+impl ExtensionDataType for Celsius {
+    fn resolve_name(&self) -> String {
+        "celsius".to_string()
+    }
+
+    fn size(&self, _config: &Value) -> Option<usize> {
+        Some(4)
+    }
+
+    fn array_data_type(&self, _config: &Value) -> Result<DataType, CharizarrError> {
+        Ok(DataType::Core(charizarr::data_type::CoreDataType::Float32))
+    }
+
+    fn parse_fill_value(&self, _config: &Value, fill_value: &Value) -> Result<Value, CharizarrError> {
+        match fill_value.as_str() {
+            Some("freezing") => Ok(serde_json::json!(0.0)),
+            _ => Ok(fill_value.clone()),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_extension_data_type() {
+    let (store, _dir) = temp_store("extension");
+
+    let data_type = DataType::Extension(Extension {
+        name: "celsius".to_string(),
+        configuration: serde_json::json!({}),
+    });
+    let array = charizarr::array::Array::create(
+        &store,
+        Some("temperature".into()),
+        None,
+        vec![3],
+        vec![3],
+        None,
+        data_type,
+        serde_json::json!("freezing"),
+        vec![Extension {
+            name: "bytes".to_string(),
+            configuration: serde_json::json!({"endian": "little"}),
+        }],
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+
+    // Without the registry the fill value can not be parsed
+    assert!(array.get(None).await.is_err());
+
+    let registry = DataTypeRegistry::default().register(Arc::new(Celsius {}));
+    let array = array.with_data_type_registry(registry);
+    let chunk = ZArray::from(vec![-1.5f32, 0.0, 21.0]);
+    array.set_chunk(&[0], &chunk).await.unwrap();
+    let data = array.get(None).await.unwrap();
+    assert_eq!(data, chunk);
+}
+
+#[tokio::test]
+async fn test_typed_array() {
+    let (store, _dir) = temp_store("typed");
+
+    let array = charizarr::array::Array::create(
+        &store,
+        Some("values".into()),
+        None,
+        vec![2, 2],
+        vec![2, 2],
+        None,
+        DataType::Core(charizarr::data_type::CoreDataType::Float64),
+        serde_json::json!(0.0),
+        vec![Extension {
+            name: "bytes".to_string(),
+            configuration: serde_json::json!({"endian": "little"}),
+        }],
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let array = array.typed::<f64>().unwrap();
+    let values = Array::from_shape_vec(IxDyn(&[2, 2]), vec![1.0, 2.0, 3.0, 4.0]).unwrap();
+    array.set_chunk(&[0, 0], values.view()).await.unwrap();
+    let data: ArrayD<f64> = array.get(None).await.unwrap();
+    assert_eq!(data, values);
+
+    let values = values.mapv(|v| v * 2.0);
+    array.set(None, values.view()).await.unwrap();
+    let data: ArrayD<f64> = array.get_chunk(&[0, 0]).await.unwrap();
+    assert_eq!(data, values);
+
+    let array: Result<TypedArray<_, i32>, _> =
+        TypedArray::open(&store, Some("values".into()), None).await;
+    assert!(array.is_err());
+}
+
+#[tokio::test]
+async fn test_basic_selection() {
+    let (store, _dir) = temp_store("selection");
+
+    let array = charizarr::array::Array::create(
+        &store,
+        Some("grid".into()),
+        None,
+        vec![6, 4],
+        vec![3, 2],
+        None,
+        DataType::Core(charizarr::data_type::CoreDataType::Int32),
+        serde_json::json!(0),
+        vec![Extension {
+            name: "bytes".to_string(),
+            configuration: serde_json::json!({"endian": "little"}),
+        }],
+        None,
+        None,
+    )
+    .await
+    .unwrap()
+    .typed::<i32>()
+    .unwrap();
+
+    // Each chunk is written whole, so the values are 0..24 in row major order
+    let expected = Array::from_shape_vec(IxDyn(&[6, 4]), (0..24).collect()).unwrap();
+    for (i, j) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+        let chunk = expected.slice(s![i * 3..(i + 1) * 3, j * 2..(j + 1) * 2]);
+        array.set_chunk(&[i, j], chunk.into_dyn()).await.unwrap();
+    }
+
+    let data = array.get_basic_selection(s![1..;2, -1]).await.unwrap();
+    assert_eq!(data, expected.slice(s![1..;2, -1]).into_dyn());
+
+    let data = array.get_basic_selection(s![-2.., 1..4;2]).await.unwrap();
+    assert_eq!(data, expected.slice(s![-2.., 1..4;2]).into_dyn());
+
+    let data = array.get_basic_selection(s![2, ..]).await.unwrap();
+    assert_eq!(data, expected.slice(s![2, ..]).into_dyn());
+
+    assert!(array.get_basic_selection(s![0..7, ..]).await.is_err());
+    assert!(array.get_basic_selection(s![6, ..]).await.is_err());
+
+    // Write every other row of the last column
+    let values = Array::from_vec(vec![-1, -2, -3]).into_dyn();
+    array.set_basic_selection(s![..;2, 3], values.view()).await.unwrap();
+
+    let mut expected = expected;
+    expected.slice_mut(s![..;2, 3]).assign(&values);
+    let data = array.get(None).await.unwrap();
+    assert_eq!(data, expected);
+
+    assert!(array.set_basic_selection(s![..;2, 2..], values.view()).await.is_err());
+}
+
+#[tokio::test]
+async fn test_orthogonal_selection() {
+    let (store, _dir) = temp_store("orthogonal");
+
+    let array = charizarr::array::Array::create(
+        &store,
+        Some("grid".into()),
+        None,
+        vec![6, 4],
+        vec![3, 2],
+        None,
+        DataType::Core(charizarr::data_type::CoreDataType::Int32),
+        serde_json::json!(0),
+        vec![Extension {
+            name: "bytes".to_string(),
+            configuration: serde_json::json!({"endian": "little"}),
+        }],
+        None,
+        None,
+    )
+    .await
+    .unwrap()
+    .typed::<i32>()
+    .unwrap();
+
+    let expected = Array::from_shape_vec(IxDyn(&[6, 4]), (0..24).collect()).unwrap();
+    for (i, j) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+        let chunk = expected.slice(s![i * 3..(i + 1) * 3, j * 2..(j + 1) * 2]);
+        array.set_chunk(&[i, j], chunk.into_dyn()).await.unwrap();
+    }
+
+    // Unsorted and repeated rows, with a mask over the columns
+    let data = array
+        .get_orthogonal_selection(vec![
+            OrthogonalElem::from(vec![5usize, 0, 5]),
+            OrthogonalElem::from(vec![false, true, false, true]),
+        ])
+        .await
+        .unwrap();
+    assert_eq!(data, array![[21, 23], [1, 3], [21, 23]].into_dyn());
+
+    let data = array
+        .get_orthogonal_selection(vec![OrthogonalElem::Index(-1), OrthogonalElem::from(vec![2usize, 0])])
+        .await
+        .unwrap();
+    assert_eq!(data, array![22, 20].into_dyn());
+
+    assert!(array
+        .get_orthogonal_selection(vec![vec![6usize]])
+        .await
+        .is_err());
+
+    // Write the corners of the grid
+    let values = array![[-1, -2], [-3, -4]].into_dyn();
+    array
+        .set_orthogonal_selection(vec![vec![0, 5], vec![0, 3]], values.view())
+        .await
+        .unwrap();
+
+    let mut expected = expected;
+    expected[[0, 0]] = -1;
+    expected[[0, 3]] = -2;
+    expected[[5, 0]] = -3;
+    expected[[5, 3]] = -4;
+    let data = array.get(None).await.unwrap();
+    assert_eq!(data, expected);
+
+    assert!(array
+        .set_orthogonal_selection(vec![vec![0usize, 5]], values.view())
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_coordinate_selection() {
+    let (store, _dir) = temp_store("coordinate");
+
+    let array = charizarr::array::Array::create(
+        &store,
+        Some("grid".into()),
+        None,
+        vec![6, 4],
+        vec![3, 2],
+        None,
+        DataType::Core(charizarr::data_type::CoreDataType::Int32),
+        serde_json::json!(0),
+        vec![Extension {
+            name: "bytes".to_string(),
+            configuration: serde_json::json!({"endian": "little"}),
+        }],
+        None,
+        None,
+    )
+    .await
+    .unwrap()
+    .typed::<i32>()
+    .unwrap();
+
+    let expected = Array::from_shape_vec(IxDyn(&[6, 4]), (0..24).collect()).unwrap();
+    for (i, j) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+        let chunk = expected.slice(s![i * 3..(i + 1) * 3, j * 2..(j + 1) * 2]);
+        array.set_chunk(&[i, j], chunk.into_dyn()).await.unwrap();
+    }
+
+    let selection = CoordinateSelection::new(vec![vec![5, 0, -1, 2], vec![0, 3, 1, 2]]);
+    let data = array.get_coordinate_selection(selection.clone()).await.unwrap();
+    assert_eq!(data, array![20, 3, 21, 10].into_dyn());
+
+    let points = CoordinateSelection::from_points(&[vec![1, 1], vec![4, 2]]).unwrap();
+    let data = array.get_coordinate_selection(points).await.unwrap();
+    assert_eq!(data, array![5, 18].into_dyn());
+
+    let values = array![-1, -2, -3, -4].into_dyn();
+    array
+        .set_coordinate_selection(selection.clone(), values.view())
+        .await
+        .unwrap();
+    let data = array.get_coordinate_selection(selection.clone()).await.unwrap();
+    assert_eq!(data, values);
+
+    let mut expected = expected;
+    expected[[5, 0]] = -1;
+    expected[[0, 3]] = -2;
+    expected[[5, 1]] = -3;
+    expected[[2, 2]] = -4;
+    assert_eq!(array.get(None).await.unwrap(), expected);
+
+    assert!(array
+        .set_coordinate_selection(selection, values.slice(s![..2]).into_dyn())
+        .await
+        .is_err());
+    let out_of_bounds = CoordinateSelection::new(vec![vec![6], vec![0]]);
+    assert!(array.get_coordinate_selection(out_of_bounds).await.is_err());
+}
+
+#[tokio::test]
+async fn test_mask_selection() {
+    let (store, _dir) = temp_store("mask");
+
+    let array = charizarr::array::Array::create(
+        &store,
+        Some("grid".into()),
+        None,
+        vec![6, 4],
+        vec![3, 2],
+        None,
+        DataType::Core(charizarr::data_type::CoreDataType::Float32),
+        serde_json::json!(0.0),
+        vec![Extension {
+            name: "bytes".to_string(),
+            configuration: serde_json::json!({"endian": "little"}),
+        }],
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+
+    // Only the first chunk is written, the mask never touches the others
+    let chunk = Array::from_shape_vec(IxDyn(&[3, 2]), vec![0.0f32, 1.0, 2.0, 3.0, 4.0, 5.0]).unwrap();
+    array.set_chunk(&[0, 0], &ZArray::Float32(chunk)).await.unwrap();
+
+    let mut mask = ArrayD::from_elem(vec![6, 4], false);
+    mask[[0, 1]] = true;
+    mask[[2, 0]] = true;
+    let mask = ZArray::Bool(mask);
+
+    let data = array.get_mask_selection(&mask).await.unwrap();
+    assert_eq!(data, ZArray::Float32(array![1.0f32, 4.0].into_dyn()));
+
+    let values = ZArray::Float32(array![-1.0f32, -4.0].into_dyn());
+    array.set_mask_selection(&mask, &values).await.unwrap();
+    let data = array.get_chunk(&[0, 0]).await.unwrap();
+    assert_eq!(
+        data,
+        ZArray::Float32(array![[0.0f32, -1.0], [2.0, 3.0], [-4.0, 5.0]].into_dyn())
+    );
+
+    let wrong_shape = ZArray::Bool(ArrayD::from_elem(vec![6, 3], false));
+    assert!(array.get_mask_selection(&wrong_shape).await.is_err());
+    let not_bool = ZArray::Int8(ArrayD::zeros(vec![6, 4]));
+    assert!(array.get_mask_selection(&not_bool).await.is_err());
+    let too_many = ZArray::Float32(array![1.0f32, 2.0, 3.0].into_dyn());
+    assert!(array.set_mask_selection(&mask, &too_many).await.is_err());
+}
+
+#[tokio::test]
+#[allow(clippy::single_range_in_vec_init, clippy::reversed_empty_ranges)]
+async fn test_selection_validation() {
+    let (store, _dir) = temp_store("validation");
+
+    let array = charizarr::array::Array::create(
+        &store,
+        Some("grid".into()),
+        None,
+        vec![4, 4],
+        vec![2, 2],
+        None,
+        DataType::Core(charizarr::data_type::CoreDataType::Int32),
+        serde_json::json!(0),
+        vec![Extension {
+            name: "bytes".to_string(),
+            configuration: serde_json::json!({"endian": "little"}),
+        }],
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let chunk = ZArray::Int32(ArrayD::zeros(vec![2, 2]));
+    array.set_chunk(&[0, 0], &chunk).await.unwrap();
+
+    // Rank and bounds of the selection
+    let err = array.get(Some(vec![0..2])).await.unwrap_err();
+    assert!(matches!(err, CharizarrError::ArrayError(_)));
+    assert!(array.get(Some(vec![0..2, 0..5])).await.is_err());
+    assert!(array.get(Some(vec![2..1, 0..2])).await.is_err());
+    assert!(array.get(Some(vec![0..2, 0..2])).await.is_ok());
+
+    // Shape and data type of the values
+    let err = array.set(Some(vec![0..2, 0..2]), &ZArray::Int32(ArrayD::zeros(vec![2, 1]))).await;
+    assert!(matches!(err, Err(CharizarrError::ArrayError(_))));
+    let err = array.set(Some(vec![0..2, 0..2]), &ZArray::Float32(ArrayD::zeros(vec![2, 2]))).await;
+    assert!(matches!(err, Err(CharizarrError::TypeError(_))));
+
+    // A chunk with the wrong number of values is an error rather than a panic
+    array.set_raw_chunk(&[0, 0], &[0u8; 12]).await.unwrap();
+    assert!(array.get_chunk(&[0, 0]).await.is_err());
+    assert!(array.get(Some(vec![0..2, 0..2])).await.is_err());
+}
+
+#[tokio::test]
+async fn test_edge_chunks() {
+    let (store, _dir) = temp_store("edge");
+
+    // No dimension is a multiple of the chunk shape
+    let array = charizarr::array::Array::create(
+        &store,
+        Some("cube".into()),
+        None,
+        vec![5, 7, 3],
+        vec![2, 3, 2],
+        None,
+        DataType::Core(charizarr::data_type::CoreDataType::Int16),
+        serde_json::json!(-1),
+        vec![Extension {
+            name: "bytes".to_string(),
+            configuration: serde_json::json!({"endian": "little"}),
+        }],
+        None,
+        None,
+    )
+    .await
+    .unwrap()
+    .typed::<i16>()
+    .unwrap();
+
+    assert_eq!(array.array().chunk_grid_shape(), vec![3, 3, 2]);
+    assert_eq!(array.array().clipped_chunk_shape(&[2, 2, 1]), vec![1, 1, 1]);
+    assert_eq!(array.array().clipped_chunk_shape(&[0, 1, 0]), vec![2, 3, 2]);
+
+    // Nothing has been written, so the whole array is the fill value
+    let data = array.get(None).await.unwrap();
+    assert_eq!(data, ArrayD::from_elem(vec![5, 7, 3], -1));
+
+    // Write a region touching the trailing edge of every dimension, which
+    // only creates partial edge chunks
+    let values = Array::from_shape_vec(IxDyn(&[2, 3, 1]), (0..6).collect()).unwrap();
+    array.set(Some(vec![3..5, 4..7, 2..3]), values.view()).await.unwrap();
+
+    let mut expected = ArrayD::from_elem(vec![5, 7, 3], -1i16);
+    expected.slice_mut(s![3..5, 4..7, 2..3]).assign(&values);
+    assert_eq!(array.get(None).await.unwrap(), expected);
+
+    // On disk the edge chunk is full size, padded with the fill value
+    let chunk = array.get_chunk(&[2, 2, 1]).await.unwrap();
+    assert_eq!(chunk.shape(), &[2, 3, 2]);
+    assert_eq!(chunk[[0, 0, 0]], 5);
+    assert_eq!(chunk.iter().filter(|v| **v == -1).count(), 11);
+
+    // Write the whole array, then read a selection crossing the edges
+    let values = Array::from_shape_vec(IxDyn(&[5, 7, 3]), (0..105).collect()).unwrap();
+    array.set(None, values.view()).await.unwrap();
+    assert_eq!(array.get(None).await.unwrap(), values);
+    let data = array.get(Some(vec![1..5, 5..7, 1..3])).await.unwrap();
+    assert_eq!(data, values.slice(s![1..5, 5..7, 1..3]).into_dyn());
+
+    // Edge chunks may also be written clipped to the array bounds
+    let clipped = ArrayD::from_elem(vec![1, 1, 1], 42i16);
+    array.set_chunk(&[2, 2, 1], clipped.view()).await.unwrap();
+    let data = array.get(Some(vec![4..5, 6..7, 2..3])).await.unwrap();
+    assert_eq!(data, clipped);
+    let wrong = ArrayD::from_elem(vec![1, 2, 1], 42i16);
+    assert!(array.set_chunk(&[2, 2, 1], wrong.view()).await.is_err());
+}
+
+#[tokio::test]
+async fn test_full_chunk_writes() {
+    let (store, _dir) = temp_store("full_chunks");
+
+    let array = charizarr::array::Array::create(
+        &store,
+        Some("grid".into()),
+        None,
+        vec![4, 3],
+        vec![2, 2],
+        None,
+        DataType::Core(charizarr::data_type::CoreDataType::UInt8),
+        serde_json::json!(9),
+        vec![Extension {
+            name: "bytes".to_string(),
+            configuration: serde_json::json!({"endian": "little"}),
+        }],
+        None,
+        None,
+    )
+    .await
+    .unwrap()
+    .typed::<u8>()
+    .unwrap();
+
+    // Corrupt chunks can not be read, so writes only succeed if they do not
+    // read the chunk first
+    for id in [[0, 0], [0, 1], [1, 0], [1, 1]] {
+        array.array().set_raw_chunk(&id, &[0u8; 3]).await.unwrap();
+    }
+
+    // Covers chunk [0, 0] entirely and the clipped edge chunk [0, 1]
+    let values = Array::from_shape_vec(IxDyn(&[2, 3]), (0..6).collect()).unwrap();
+    array.set(Some(vec![0..2, 0..3]), values.view()).await.unwrap();
+    assert_eq!(array.get(Some(vec![0..2, 0..3])).await.unwrap(), values);
+    let edge = array.get_chunk(&[0, 1]).await.unwrap();
+    assert_eq!(edge, array![[2, 9], [5, 9]].into_dyn());
+
+    // Partially covering a corrupt chunk has to read it
+    let values = ArrayD::from_elem(vec![1, 3], 1u8);
+    assert!(array.set(Some(vec![2..3, 0..3]), values.view()).await.is_err());
+
+    // Missing chunks that are partially covered are filled with the fill value
+    array.array().erase_chunk(&[1, 0]).await.unwrap();
+    let values = ArrayD::from_elem(vec![1, 1], 1u8);
+    array.set(Some(vec![3..4, 1..2]), values.view()).await.unwrap();
+    let chunk = array.get_chunk(&[1, 0]).await.unwrap();
+    assert_eq!(chunk, array![[9, 9], [9, 1]].into_dyn());
+}
+
+#[tokio::test]
+async fn test_bounded_concurrency() {
+    let (store, _dir) = temp_store("concurrency");
+
+    let array = charizarr::array::Array::create(
+        &store,
+        Some("grid".into()),
+        None,
+        vec![20, 20],
+        vec![3, 3],
+        None,
+        DataType::Core(charizarr::data_type::CoreDataType::UInt16),
+        serde_json::json!(0),
+        vec![Extension {
+            name: "bytes".to_string(),
+            configuration: serde_json::json!({"endian": "little"}),
+        }],
+        None,
+        None,
+    )
+    .await
+    .unwrap()
+    .with_concurrency(0);
+    assert_eq!(array.concurrency(), 1);
+
+    let array = array.with_concurrency(4).typed::<u16>().unwrap();
+    let values = Array::from_shape_vec(IxDyn(&[20, 20]), (0..400).collect()).unwrap();
+    array.set(None, values.view()).await.unwrap();
+    assert_eq!(array.get(None).await.unwrap(), values);
+
+    let data = array.get(Some(vec![5..17, 2..19])).await.unwrap();
+    assert_eq!(data, values.slice(s![5..17, 2..19]).into_dyn());
+
+    // Erased chunks read as the fill value, and erasing twice is not an error
+    let ids = vec![vec![0, 0], vec![6, 6]];
+    array.array().erase_chunks(&ids).await.unwrap();
+    array.array().erase_chunks(&ids).await.unwrap();
+    let mut expected = values;
+    expected.slice_mut(s![0..3, 0..3]).fill(0);
+    expected.slice_mut(s![18..20, 18..20]).fill(0);
+    assert_eq!(array.get(None).await.unwrap(), expected);
+}
+
+#[tokio::test]
+async fn test_codec_execution() {
+    let (store, _dir) = temp_store("codec_execution");
+    let codec_registry = charizarr::codec_registry::CodecRegistry::default()
+        .register(Codec::ByteToByte(Arc::new(GZipCodec::new())));
+
+    let executions = [
+        CodecExecution::Inline,
+        CodecExecution::SpawnBlocking,
+        #[cfg(feature = "rayon")]
+        CodecExecution::Rayon,
+    ];
+
+    for (i, execution) in executions.into_iter().enumerate() {
+        let array = charizarr::array::Array::create(
+            &store,
+            Some(format!("array{i}")),
+            Some(codec_registry.clone()),
+            vec![10, 10],
+            vec![4, 4],
+            None,
+            DataType::Core(charizarr::data_type::CoreDataType::Float64),
+            serde_json::json!(0.0),
+            vec![
+                Extension {
+                    name: "bytes".to_string(),
+                    configuration: serde_json::json!({"endian": "little"}),
+                },
+                Extension {
+                    name: "gzip".to_string(),
+                    configuration: serde_json::json!({"level": 5}),
+                },
+            ],
+            None,
+            None,
+        )
+        .await
+        .unwrap()
+        .with_codec_execution(execution)
+        .typed::<f64>()
+        .unwrap();
+
+        let values = Array::from_shape_fn(IxDyn(&[10, 10]), |i| (i[0] * 10 + i[1]) as f64);
+        array.set(None, values.view()).await.unwrap();
+        assert_eq!(array.get(None).await.unwrap(), values, "{execution:?}");
+
+        let mut buffer = ArrayD::zeros(vec![10, 10]);
+        array.get_into(s![.., ..], &mut buffer.view_mut()).await.unwrap();
+        assert_eq!(buffer, values, "{execution:?}");
+    }
+}
+
+#[tokio::test]
+async fn test_iter_chunks() {
+    let (store, _dir) = temp_store("iter_chunks");
+
+    let array = charizarr::array::Array::create(
+        &store,
+        Some("grid".into()),
+        None,
+        vec![7, 5],
+        vec![3, 2],
+        None,
+        DataType::Core(charizarr::data_type::CoreDataType::Int64),
+        serde_json::json!(0),
+        vec![Extension {
+            name: "bytes".to_string(),
+            configuration: serde_json::json!({"endian": "little"}),
+        }],
+        None,
+        None,
+    )
+    .await
+    .unwrap()
+    .typed::<i64>()
+    .unwrap();
+
+    let values = Array::from_shape_vec(IxDyn(&[7, 5]), (0..35).collect()).unwrap();
+    array.set(None, values.view()).await.unwrap();
+
+    // Ordered chunks follow the chunk grid in row major order
+    let chunks: Vec<_> = array
+        .iter_chunks(s![1..7, 1..], 2, ChunkOrder::Ordered)
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    let coords: Vec<_> = chunks.iter().map(|(p, _)| p.chunk_coords.clone()).collect();
+    let expected: Vec<_> = (0..3).flat_map(|i| (0..3).map(move |j| vec![i, j])).collect();
+    assert_eq!(coords, expected);
+
+    // Reassembling the chunks gives the selection
+    let mut data = ArrayD::zeros(vec![6, 4]);
+    for (projection, chunk) in chunks {
+        data.slice_each_axis_mut(|a| projection.out_sel[a.axis.0]).assign(&chunk);
+    }
+    assert_eq!(data, values.slice(s![1..7, 1..]).into_dyn());
+
+    // Unordered chunks hold the same data
+    let chunks: Vec<_> = array
+        .iter_chunks(s![.., 3], 4, ChunkOrder::Unordered)
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(chunks.len(), 3);
+    let total: i64 = chunks.iter().map(|(_, chunk)| chunk.sum()).sum();
+    assert_eq!(total, values.slice(s![.., 3]).sum());
+
+    assert!(array.iter_chunks(s![.., 5], 1, ChunkOrder::Ordered).is_err());
+}
+
+#[tokio::test]
+async fn test_get_into() {
+    let (store, _dir) = temp_store("get_into");
+    let codec_registry = charizarr::codec_registry::CodecRegistry::default()
+        .register(Codec::ByteToByte(Arc::new(GZipCodec::new())));
+
+    for endian in ["little", "big"] {
+        let array = charizarr::array::Array::create(
+            &store,
+            Some(endian.into()),
+            Some(codec_registry.clone()),
+            vec![8, 7],
+            vec![4, 3],
+            None,
+            DataType::Core(charizarr::data_type::CoreDataType::Float32),
+            serde_json::json!(-1.0),
+            vec![
+                Extension {
+                    name: "bytes".to_string(),
+                    configuration: serde_json::json!({ "endian": endian }),
+                },
+                Extension {
+                    name: "gzip".to_string(),
+                    configuration: serde_json::json!({"level": 1}),
+                },
+            ],
+            None,
+            None,
+        )
+        .await
+        .unwrap()
+        .typed::<f32>()
+        .unwrap();
+
+        // The last row of chunks is never written
+        let values = Array::from_shape_fn(IxDyn(&[8, 7]), |i| (i[0] * 7 + i[1]) as f32);
+        array.set(Some(vec![0..4, 0..7]), values.slice(s![0..4, ..]).into_dyn()).await.unwrap();
+        let mut expected = values.clone();
+        expected.slice_mut(s![4.., ..]).fill(-1.0);
+
+        // The same buffer is reused for every read
+        let mut buffer = ArrayD::zeros(vec![8, 7]);
+        array.get_into(s![.., ..], &mut buffer.view_mut()).await.unwrap();
+        assert_eq!(buffer, expected);
+
+        buffer.fill(0.0);
+        array.get_into(s![.., ..], &mut buffer.view_mut()).await.unwrap();
+        assert_eq!(buffer, expected);
+
+        // Selections that drop dimensions or only partly cover chunks
+        let mut row = ArrayD::zeros(vec![7]);
+        array.get_into(s![2, ..], &mut row.view_mut()).await.unwrap();
+        assert_eq!(row, expected.slice(s![2, ..]).into_dyn());
+
+        let mut part = ArrayD::zeros(vec![5, 2]);
+        array.get_into(s![1..6, 2..4], &mut part.view_mut()).await.unwrap();
+        assert_eq!(part, expected.slice(s![1..6, 2..4]).into_dyn());
+
+        // Non contiguous buffers are filled through the decoded chunks
+        let mut transposed = ArrayD::zeros(vec![7, 8]);
+        let mut view = transposed.view_mut().reversed_axes();
+        array.get_into(s![.., ..], &mut view).await.unwrap();
+        assert_eq!(transposed.t(), expected);
+
+        assert!(array.get_into(s![.., ..], &mut part.view_mut()).await.is_err());
+    }
+
+    // The element type must match the array
+    let array = charizarr::array::Array::open(&store, Some("little".into()), Some(codec_registry))
+        .await
+        .unwrap();
+    let mut wrong = ArrayD::<f64>::zeros(vec![8, 7]);
+    assert!(array.get_into(s![.., ..], &mut wrong.view_mut()).await.is_err());
+}
+
+#[tokio::test]
+async fn test_resize_append() {
+    let (store, _dir) = temp_store("resize");
+
+    let mut array = charizarr::array::Array::create(
+        &store,
+        Some("grid".into()),
+        None,
+        vec![6, 4],
+        vec![4, 2],
+        None,
+        DataType::Core(charizarr::data_type::CoreDataType::Int32),
+        serde_json::json!(0),
+        vec![Extension {
+            name: "bytes".to_string(),
+            configuration: serde_json::json!({"endian": "little"}),
+        }],
+        None,
+        None,
+    )
+    .await
+    .unwrap()
+    .typed::<i32>()
+    .unwrap();
+
+    let values = Array::from_shape_vec(IxDyn(&[6, 4]), (1..25).collect()).unwrap();
+    array.set(None, values.view()).await.unwrap();
+
+    // Shrinking erases the chunks outside of the new shape
+    array.resize(vec![3, 2]).await.unwrap();
+    assert_eq!(array.array().shape(), vec![3, 2]);
+    assert_eq!(array.get(None).await.unwrap(), values.slice(s![0..3, 0..2]).into_dyn());
+    for id in [[1, 0], [1, 1], [0, 1]] {
+        assert!(array.array().get_raw_chunk(&id).await.is_err());
+    }
+
+    // The new shape is persisted
+    let reopened = charizarr::array::Array::open(&store, Some("grid".into()), None)
+        .await
+        .unwrap();
+    assert_eq!(reopened.shape(), vec![3, 2]);
+
+    // Growing again reads the fill value where data was dropped
+    array.resize(vec![6, 4]).await.unwrap();
+    let mut expected = ArrayD::zeros(vec![6, 4]);
+    expected.slice_mut(s![0..3, 0..2]).assign(&values.slice(s![0..3, 0..2]));
+    assert_eq!(array.get(None).await.unwrap(), expected);
+
+    // Appending fills the partial last chunk and creates new ones
+    let rows = Array::from_shape_vec(IxDyn(&[3, 4]), (100..112).collect()).unwrap();
+    array.append(0, rows.view()).await.unwrap();
+    assert_eq!(array.array().shape(), vec![9, 4]);
+    let data = array.get(None).await.unwrap();
+    assert_eq!(data.slice(s![0..6, ..]).into_dyn(), expected);
+    assert_eq!(data.slice(s![6..9, ..]).into_dyn(), rows);
+
+    let cols = Array::from_shape_vec(IxDyn(&[9, 1]), (200..209).collect()).unwrap();
+    array.append(1, cols.view()).await.unwrap();
+    assert_eq!(array.array().shape(), vec![9, 5]);
+    assert_eq!(array.get(Some(vec![0..9, 4..5])).await.unwrap(), cols);
+
+    // Shrinking within the last chunk of both axes clears the cut off values
+    let before = array.get(None).await.unwrap();
+    array.resize(vec![7, 3]).await.unwrap();
+    array.resize(vec![9, 5]).await.unwrap();
+    let mut expected = ArrayD::zeros(vec![9, 5]);
+    expected.slice_mut(s![0..7, 0..3]).assign(&before.slice(s![0..7, 0..3]));
+    assert_eq!(array.get(None).await.unwrap(), expected);
+
+    // Values must match the array on every other axis
+    let bad = Array::from_shape_vec(IxDyn(&[1, 4]), vec![0; 4]).unwrap();
+    assert!(array.append(0, bad.view()).await.is_err());
+    assert!(array.append(2, cols.view()).await.is_err());
+    assert!(array.resize(vec![9]).await.is_err());
+}
+
+#[tokio::test]
+async fn test_delete() {
+    let (store, _dir) = temp_store("delete");
+
+    let group = charizarr::group::Group::create(&store, None, None).await.unwrap();
+    let nested = group.create_group("nested").await.unwrap();
+    for (parent, name) in [(&group, "first"), (&group, "second"), (&nested, "inner")] {
+        let array = parent
+            .create_array(
+                name,
+                None,
+                vec![4],
+                vec![2],
+                None,
+                DataType::Core(charizarr::data_type::CoreDataType::UInt8),
+                serde_json::json!(0),
+                vec![Extension {
+                    name: "bytes".to_string(),
+                    configuration: serde_json::json!({"endian": "little"}),
+                }],
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let values = ZArray::UInt8(ArrayD::from_elem(vec![4], 1));
+        array.set(None, &values).await.unwrap();
+    }
+
+    // A dry run lists the keys without erasing them
+    let array = group.get_array("first", None).await.unwrap();
+    let keys = array.delete(true).await.unwrap();
+    assert_eq!(keys, vec!["first/c/0", "first/c/1", "first/zarr.json"]);
+    assert!(group.get_array("first", None).await.is_ok());
+
+    let keys = array.delete(false).await.unwrap();
+    assert_eq!(keys.len(), 3);
+    assert!(store.list_prefix("first/").await.unwrap().is_empty());
+    assert!(group.get_array("first", None).await.is_err());
+
+    // Deleting a group member erases its children but not its siblings
+    let keys = group.delete_member("nested", true).await.unwrap();
+    assert_eq!(
+        keys,
+        vec!["nested/inner/c/0", "nested/inner/c/1", "nested/inner/zarr.json", "nested/zarr.json"]
+    );
+    group.delete_member("nested", false).await.unwrap();
+    assert!(group.get_group("nested").await.is_err());
+    assert!(group.get_array("second", None).await.is_ok());
+
+    // Missing members are an error
+    assert!(matches!(
+        group.delete_member("nested", false).await,
+        Err(CharizarrError::KeyNotFoundError(_))
+    ));
+
+    // Deleting the root group erases everything
+    let keys = group.delete(false).await.unwrap();
+    assert_eq!(keys, vec!["second/c/0", "second/c/1", "second/zarr.json", "zarr.json"]);
+    assert!(store.list().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_move_member() {
+    let (store, _dir) = temp_store("move");
+
+    let group = charizarr::group::Group::create(&store, None, None)
+        .await
+        .unwrap()
+        .with_concurrency(2);
+    assert_eq!(group.concurrency(), 2);
+    let nested = group.create_group("nested").await.unwrap();
+    let values = ZArray::Int64(ArrayD::from_shape_vec(vec![5], (0..5).collect()).unwrap());
+    for (parent, name) in [(&group, "data"), (&nested, "inner")] {
+        let array = parent
+            .create_array(
+                name,
+                None,
+                vec![5],
+                vec![2],
+                None,
+                DataType::Core(charizarr::data_type::CoreDataType::Int64),
+                serde_json::json!(0),
+                vec![Extension {
+                    name: "bytes".to_string(),
+                    configuration: serde_json::json!({"endian": "little"}),
+                }],
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        array.set(None, &values).await.unwrap();
+    }
+
+    // Move an array into a nested group
+    group.move_member("data", "nested/data").await.unwrap();
+    assert!(group.get_array("data", None).await.is_err());
+    let array = nested.get_array("data", None).await.unwrap();
+    assert_eq!(array.get(None).await.unwrap(), values);
+
+    // Move a whole group subtree
+    group.move_member("nested", "renamed").await.unwrap();
+    assert!(store.list_prefix("nested/").await.unwrap().is_empty());
+    let renamed = group.get_group("renamed").await.unwrap();
+    for name in ["data", "inner"] {
+        let array = renamed.get_array(name, None).await.unwrap();
+        assert_eq!(array.get(None).await.unwrap(), values);
+    }
+
+    // Missing groups above the destination are created
+    renamed.move_member("inner", "deep/er/inner").await.unwrap();
+    let deeper = renamed.get_group("deep").await.unwrap().get_group("er").await.unwrap();
+    let array = deeper.get_array("inner", None).await.unwrap();
+    assert_eq!(array.get(None).await.unwrap(), values);
+
+    // Existing destinations, destinations under an array, missing sources
+    // and moving into itself fail without changing anything
+    renamed.create_group("taken").await.unwrap();
+    let keys = store.list().await.unwrap();
+    assert!(matches!(
+        renamed.move_member("data", "taken").await,
+        Err(CharizarrError::GroupError(_))
+    ));
+    assert!(matches!(
+        renamed.move_member("data", "deep/er/inner/data").await,
+        Err(CharizarrError::GroupError(_))
+    ));
+    assert!(matches!(
+        group.move_member("missing", "other").await,
+        Err(CharizarrError::KeyNotFoundError(_))
+    ));
+    assert!(group.move_member("renamed", "renamed/deeper").await.is_err());
+    let mut after = store.list().await.unwrap();
+    let mut before = keys;
+    before.sort();
+    after.sort();
+    assert_eq!(before, after);
+}
+
+#[tokio::test]
+async fn test_copy() {
+    let (src, _src_dir) = temp_store("copy_src");
+    let (dst, _dst_dir) = temp_store("copy_dst");
+
+    let group = charizarr::group::Group::create(&src, None, None).await.unwrap();
+    let array = group
+        .create_array(
+            "values",
+            None,
+            vec![6, 6],
+            vec![3, 3],
+            None,
+            DataType::Core(charizarr::data_type::CoreDataType::Float32),
+            serde_json::json!(0.0),
+            vec![Extension {
+                name: "bytes".to_string(),
+                configuration: serde_json::json!({"endian": "little"}),
+            }],
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    let values = ZArray::Float32(ArrayD::from_shape_fn(vec![6, 6], |i| (i[0] * 6 + i[1]) as f32));
+    array.set(None, &values).await.unwrap();
+
+    // Copy the whole group into a nested path of another store
+    let options = charizarr::copy::CopyOptions::default().with_concurrency(2);
+    let report = group.copy_to(&dst, Some("backup".into()), &options).await.unwrap();
+    assert_eq!(report.copied, 6);
+    assert_eq!(report.skipped, 0);
+
+    let copied = charizarr::group::Group::open(&dst, Some("backup".into())).await.unwrap();
+    let copied_array = copied.get_array("values", None).await.unwrap();
+    assert_eq!(copied_array.get(None).await.unwrap(), values);
+    assert_eq!(
+        copied_array.get_raw_chunk(&[1, 1]).await.unwrap(),
+        array.get_raw_chunk(&[1, 1]).await.unwrap()
+    );
+
+    // Resuming only copies chunks that are missing or differ in size, and
+    // always copies the metadata
+    copied_array.erase_chunk(&[0, 1]).await.unwrap();
+    dst.set("backup/values/c/1/0", b"partial").await.unwrap();
+    let options = options.with_resume(true);
+    let report = group.copy_to(&dst, Some("backup".into()), &options).await.unwrap();
+    assert_eq!(report, charizarr::copy::CopyReport { copied: 4, skipped: 2 });
+    assert_eq!(copied_array.get(None).await.unwrap(), values);
+
+    // Changing codecs decodes and encodes each chunk
+    let codecs = vec![
+        Extension {
+            name: "bytes".to_string(),
+            configuration: serde_json::json!({"endian": "big"}),
+        },
+        Extension {
+            name: "gzip".to_string(),
+            configuration: serde_json::json!({"level": 5}),
+        },
+    ];
+    let options = charizarr::copy::CopyOptions::default().with_codecs(codecs.clone());
+    let report = array.copy_to(&dst, Some("gzipped".into()), &options).await.unwrap();
+    assert_eq!(report.copied, 5);
+    let gzipped = charizarr::array::Array::open(&dst, Some("gzipped".into()), None)
+        .await
+        .unwrap();
+    assert_eq!(gzipped.metadata.codecs, codecs);
+    assert_eq!(gzipped.get(None).await.unwrap(), values);
+
+    // Resuming with new codecs compares against the encoded size
+    let raw = gzipped.get_raw_chunk(&[1, 0]).await.unwrap();
+    gzipped.set_raw_chunk(&[1, 0], &raw[..raw.len() / 2]).await.unwrap();
+    let options = options.with_resume(true);
+    let report = array.copy_to(&dst, Some("gzipped".into()), &options).await.unwrap();
+    assert_eq!(report, charizarr::copy::CopyReport { copied: 2, skipped: 3 });
+    assert_eq!(gzipped.get(None).await.unwrap(), values);
+    assert!(group.copy_to(&dst, Some("other".into()), &options).await.is_err());
+
+    // Arrays with no dimensions have a single chunk
+    let scalar = charizarr::array::Array::create(
+        &src,
+        Some("scalar".into()),
+        None,
+        vec![],
+        vec![],
+        None,
+        DataType::Core(charizarr::data_type::CoreDataType::Float64),
+        serde_json::json!(0.0),
+        vec![Extension {
+            name: "bytes".to_string(),
+            configuration: serde_json::json!({"endian": "little"}),
+        }],
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let value = ZArray::Float64(ArrayD::from_elem(vec![], 4.5));
+    scalar.set_chunk(&[], &value).await.unwrap();
+    let report = scalar.copy_to(&dst, Some("scalar".into()), &options).await.unwrap();
+    assert_eq!(report.copied, 2);
+    let copied_scalar = charizarr::array::Array::open(&dst, Some("scalar".into()), None)
+        .await
+        .unwrap();
+    assert_eq!(copied_scalar.get_chunk(&[]).await.unwrap(), value);
+}
+
+#[tokio::test]
+async fn test_list_keys() {
+    let (store, _dir) = temp_store("list_keys");
+    for key in ["zarr.json", "data/zarr.json", "data/c/0/0", "data/c/1/0"] {
+        store.set(key, b"{}").await.unwrap();
+    }
+
+    // Keys are relative to the store root, so they can be read back directly
+    let mut keys = store.list().await.unwrap();
+    keys.sort();
+    assert_eq!(keys, vec!["data/c/0/0", "data/c/1/0", "data/zarr.json", "zarr.json"]);
+    for key in &keys {
+        assert_eq!(store.get(key).await.unwrap(), b"{}");
+    }
+
+    let mut keys = store.list_prefix("data/c/").await.unwrap();
+    keys.sort();
+    assert_eq!(keys, vec!["data/c/0/0", "data/c/1/0"]);
+}
+
+#[tokio::test]
+async fn test_rechunk() {
+    let (store, _dir) = temp_store("rechunk");
+
+    // One chunk per time step
+    let array = charizarr::array::Array::create(
+        &store,
+        Some("spatial".into()),
+        None,
+        vec![12, 6, 5],
+        vec![1, 6, 5],
+        None,
+        DataType::Core(charizarr::data_type::CoreDataType::UInt16),
+        serde_json::json!(0),
+        vec![Extension {
+            name: "bytes".to_string(),
+            configuration: serde_json::json!({"endian": "little"}),
+        }],
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let values = ZArray::UInt16(ArrayD::from_shape_fn(vec![12, 6, 5], |i| {
+        (i[0] * 100 + i[1] * 10 + i[2]) as u16
+    }));
+    array.set(None, &values).await.unwrap();
+
+    // Rechunk to whole time series of small tiles, with a budget of a few
+    // chunks so the array is copied in many blocks
+    let codecs = vec![
+        Extension {
+            name: "bytes".to_string(),
+            configuration: serde_json::json!({"endian": "little"}),
+        },
+        Extension {
+            name: "gzip".to_string(),
+            configuration: serde_json::json!({"level": 1}),
+        },
+    ];
+    let options = charizarr::rechunk::RechunkOptions::default()
+        .with_max_mem(12 * 4 * 2 * 3)
+        .with_codecs(codecs.clone());
+    let temporal = array
+        .rechunk_to(&store, Some("temporal".into()), vec![12, 4, 2], &options)
+        .await
+        .unwrap();
+    assert_eq!(temporal.chunk_shape(), vec![12, 4, 2]);
+    assert_eq!(temporal.get(None).await.unwrap(), values);
+
+    let reopened = charizarr::array::Array::open(&store, Some("temporal".into()), None)
+        .await
+        .unwrap();
+    assert_eq!(reopened.chunk_shape(), vec![12, 4, 2]);
+    assert_eq!(reopened.metadata.codecs, codecs);
+    assert_eq!(reopened.get(None).await.unwrap(), values);
+
+    // The trailing edge chunk holds a whole time series
+    let chunk: ArrayD<u16> = reopened.get_chunk(&[0, 1, 2]).await.unwrap().try_into().unwrap();
+    assert_eq!(chunk[[11, 1, 0]], 1154);
+    assert_eq!(chunk[[11, 1, 1]], 0);
+
+    assert!(array
+        .rechunk_to(&store, Some("bad".into()), vec![12, 4], &options)
+        .await
+        .is_err());
+
+    // Arrays with no dimensions are copied as a single chunk
+    let scalar = charizarr::array::Array::create(
+        &store,
+        Some("scalar".into()),
+        None,
+        vec![],
+        vec![],
+        None,
+        DataType::Core(charizarr::data_type::CoreDataType::UInt16),
+        serde_json::json!(0),
+        vec![Extension {
+            name: "bytes".to_string(),
+            configuration: serde_json::json!({"endian": "little"}),
+        }],
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let value = ZArray::UInt16(ArrayD::from_elem(vec![], 7));
+    scalar.set(None, &value).await.unwrap();
+    assert_eq!(scalar.get(None).await.unwrap(), value);
+    let rechunked = scalar
+        .rechunk_to(&store, Some("scalar_rechunked".into()), vec![], &options)
+        .await
+        .unwrap();
+    assert_eq!(rechunked.get_chunk(&[]).await.unwrap(), value);
+    assert_eq!(rechunked.concurrency(), scalar.concurrency());
+
+    // Variable length strings need an estimate of their size
+    let strings = charizarr::array::Array::create(
+        &store,
+        Some("strings".into()),
+        None,
+        vec![6],
+        vec![2],
+        None,
+        DataType::Core(charizarr::data_type::CoreDataType::String),
+        serde_json::json!(""),
+        vec![Extension {
+            name: "vlen-utf8".to_string(),
+            configuration: serde_json::json!({}),
+        }],
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let value = ZArray::from(vec!["a", "bb", "", "ccc", "d", "ee"].into_iter().map(String::from).collect::<Vec<_>>());
+    strings.set(None, &value).await.unwrap();
+    let options = charizarr::rechunk::RechunkOptions::default().with_max_mem(256);
+    assert!(strings
+        .rechunk_to(&store, Some("strings_rechunked".into()), vec![3], &options)
+        .await
+        .is_err());
+    let rechunked = strings
+        .rechunk_to(&store, Some("strings_rechunked".into()), vec![3], &options.with_element_size(32))
+        .await
+        .unwrap();
+    assert_eq!(rechunked.get(None).await.unwrap(), value);
+}
+
+/// Counts the values read from a store
+struct CountingStore {
+    inner: charizarr::stores::ZarrObjectStore,
+    reads: std::sync::atomic::AtomicUsize,
+}
+
+impl ReadableStore for CountingStore {
+    async fn get(&self, key: &str) -> Result<Vec<u8>, CharizarrError> {
+        self.reads.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        self.inner.get(key).await
+    }
+}
+
+impl ListableStore for CountingStore {
+    async fn list(&self) -> Result<Vec<String>, CharizarrError> {
+        self.inner.list().await
+    }
+
+    async fn list_prefix(&self, prefix: &str) -> Result<Vec<String>, CharizarrError> {
+        self.inner.list_prefix(prefix).await
+    }
+
+    async fn list_dir(&self, prefix: Option<&str>) -> Result<Vec<String>, CharizarrError> {
+        self.inner.list_dir(prefix).await
+    }
+}
+
+impl WriteableStore for CountingStore {
+    async fn set(&self, key: &str, value: &[u8]) -> Result<(), CharizarrError> {
+        self.inner.set(key, value).await
+    }
+
+    async fn erase(&self, key: &str) -> Result<(), CharizarrError> {
+        self.inner.erase(key).await
+    }
+
+    async fn erase_values(&self, keys: &[&str]) -> Result<(), CharizarrError> {
+        self.inner.erase_values(keys).await
+    }
+
+    async fn erase_prefix(&self, prefix: &str) -> Result<(), CharizarrError> {
+        self.inner.erase_prefix(prefix).await
+    }
+}
+
+#[tokio::test]
+async fn test_rechunk_reads() {
+    let (inner, _dir) = temp_store("rechunk_reads");
+    let store = CountingStore {
+        inner,
+        reads: Default::default(),
+    };
+
+    let array = charizarr::array::Array::create(
+        &store,
+        Some("src".into()),
+        None,
+        vec![24, 12],
+        vec![4, 6],
+        None,
+        DataType::Core(charizarr::data_type::CoreDataType::UInt16),
+        serde_json::json!(0),
+        vec![Extension {
+            name: "bytes".to_string(),
+            configuration: serde_json::json!({"endian": "little"}),
+        }],
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let values = ZArray::UInt16(ArrayD::from_shape_fn(vec![24, 12], |i| (i[0] * 12 + i[1]) as u16));
+    array.set(None, &values).await.unwrap();
+
+    // The budget holds 18 rows, so blocks of whole rows of new chunks would
+    // split a row of source chunks and read it twice. Blocks aligned to both
+    // chunk shapes hold 12 rows and read each of the 12 source chunks once.
+    let store_reads = store.reads.load(std::sync::atomic::Ordering::SeqCst);
+    let options = charizarr::rechunk::RechunkOptions::default().with_max_mem(18 * 12 * 2 * 2);
+    let rechunked = array
+        .rechunk_to(&store, Some("dst".into()), vec![6, 4], &options)
+        .await
+        .unwrap();
+    assert_eq!(store.reads.load(std::sync::atomic::Ordering::SeqCst) - store_reads, 12);
+    assert_eq!(rechunked.get(None).await.unwrap(), values);
+}